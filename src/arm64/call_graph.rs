@@ -0,0 +1,339 @@
+use super::{dot, helper, AArch64LifterError, CallMode, EdgeKind, ElfFunction, LabelResolver};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::{self, Write};
+use yaxpeax_arch::Arch;
+use yaxpeax_arm::armv8::a64::ARMv8;
+
+const INSTRUCTION_SIZE: u64 = 4;
+
+/// Node that indirect calls point to
+const INDIRECT_TARGET: &str = "indirect";
+
+/// Function of a call graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraphFunction {
+    /// Symbol name, or `sub_<address>` for functions found without symbols
+    pub name: String,
+    /// Virtual address of the first instruction
+    pub address: u64,
+    /// Address after the last instruction
+    pub end: u64,
+}
+
+/// How a call site transfers control
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallKind {
+    /// `BL` or `BLR`, returning to the caller
+    Call,
+    /// `B`, a conditional branch or a `BR` without a recovered jump table leaving the caller,
+    /// which the callee returns from in its place
+    TailCall,
+}
+
+/// Function a call site transfers control to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallTarget {
+    /// Function of the graph, by its address
+    Function(u64),
+    /// Address outside of all functions of the graph
+    External(u64),
+    /// Target only known at runtime (`BLR`, or `BR` without a recovered jump table)
+    Indirect,
+}
+
+/// Call from one function of a call graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallSite {
+    /// Address of the calling function
+    pub caller: u64,
+    /// Address of the call instruction
+    pub pc: u64,
+    /// How control is transferred
+    pub kind: CallKind,
+    /// Function control is transferred to
+    pub target: CallTarget,
+}
+
+/// Function whose control flow could not be resolved, so that its calls are missing from the
+/// call graph
+#[derive(Debug)]
+pub struct CallGraphFailure {
+    /// Address of the function
+    pub function: u64,
+    /// Error resolving its control flow
+    pub error: AArch64LifterError,
+}
+
+/// Call graph of a set of functions, built from the control flow found by the [`LabelResolver`]
+/// of each function.
+///
+/// Branches into the middle of another function are attributed to that function. Indirect calls
+/// and indirect tail calls are recorded as call sites but not followed, since their targets are
+/// unknown. Functions whose control flow cannot be resolved stay in the graph without calls and
+/// are reported by [`CallGraph::failures`].
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    functions: BTreeMap<u64, CallGraphFunction>,
+    calls: Vec<CallSite>,
+    failures: Vec<CallGraphFailure>,
+}
+
+impl CallGraph {
+    /// Build the call graph of `functions`, which must not overlap
+    pub fn new(functions: &[ElfFunction]) -> Self {
+        let mut graph = Self {
+            functions: functions
+                .iter()
+                .map(|function| {
+                    let node = CallGraphFunction {
+                        name: function.name.clone(),
+                        address: function.address,
+                        end: function.address + function.code.len() as u64,
+                    };
+                    (function.address, node)
+                })
+                .collect(),
+            calls: Vec::new(),
+            failures: Vec::new(),
+        };
+
+        let decoder = <ARMv8 as Arch>::Decoder::default();
+        for function in functions {
+            let resolver = match LabelResolver::with_call_mode(
+                function.code,
+                function.address,
+                CallMode::Call,
+                &decoder,
+            ) {
+                Ok(resolver) => resolver,
+                Err(error) => {
+                    graph.failures.push(CallGraphFailure {
+                        function: function.address,
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let cfg = resolver.control_flow_graph();
+            let end = function.address + function.code.len() as u64;
+            let inside = |addr: u64| (function.address..end).contains(&addr);
+
+            for node in cfg.nodes() {
+                // control transfers end their node
+                let pc = node.end - INSTRUCTION_SIZE;
+                for edge in &node.successors {
+                    let (kind, target) = match (edge.kind, edge.to) {
+                        (EdgeKind::Call, Some(to)) => (CallKind::Call, graph.target(to)),
+                        (EdgeKind::Call, None) => (CallKind::Call, CallTarget::Indirect),
+                        (
+                            EdgeKind::Branch | EdgeKind::Conditional | EdgeKind::Dynamic,
+                            Some(to),
+                        ) if !inside(to) => (CallKind::TailCall, graph.target(to)),
+                        (EdgeKind::Dynamic, None) => (CallKind::TailCall, CallTarget::Indirect),
+                        _ => continue,
+                    };
+                    graph.calls.push(CallSite {
+                        caller: function.address,
+                        pc,
+                        kind,
+                        target,
+                    });
+                }
+            }
+        }
+        graph.calls.sort_by_key(|call| (call.caller, call.pc));
+
+        graph
+    }
+
+    /// Functions sorted by address
+    pub fn functions(&self) -> impl Iterator<Item = &CallGraphFunction> + '_ {
+        self.functions.values()
+    }
+
+    /// Function starting at `address`
+    pub fn function(&self, address: u64) -> Option<&CallGraphFunction> {
+        self.functions.get(&address)
+    }
+
+    /// Function containing the instruction at `address`
+    pub fn function_containing(&self, address: u64) -> Option<&CallGraphFunction> {
+        self.functions
+            .range(..=address)
+            .next_back()
+            .map(|(_, function)| function)
+            .filter(|function| address < function.end)
+    }
+
+    /// Functions whose control flow could not be resolved, sorted by address
+    pub fn failures(&self) -> &[CallGraphFailure] {
+        &self.failures
+    }
+
+    /// All call sites, sorted by caller and address
+    pub fn calls(&self) -> &[CallSite] {
+        &self.calls
+    }
+
+    /// Call sites of the function at `caller`
+    pub fn callees(&self, caller: u64) -> impl Iterator<Item = &CallSite> + '_ {
+        self.calls.iter().filter(move |call| call.caller == caller)
+    }
+
+    /// Call sites calling the function at `callee`
+    pub fn callers(&self, callee: u64) -> impl Iterator<Item = &CallSite> + '_ {
+        self.calls
+            .iter()
+            .filter(move |call| call.target == CallTarget::Function(callee))
+    }
+
+    /// Addresses of the functions that can reach the instruction at `address`, such as a system
+    /// call or trap, through direct calls and tail calls, including the function containing it
+    pub fn reaching(&self, address: u64) -> BTreeSet<u64> {
+        let mut reaching = BTreeSet::new();
+        let Some(function) = self.function_containing(address) else {
+            return reaching;
+        };
+        let mut worklist = VecDeque::from([function.address]);
+        while let Some(function) = worklist.pop_front() {
+            if reaching.insert(function) {
+                worklist.extend(self.callers(function).map(|call| call.caller));
+            }
+        }
+        reaching
+    }
+
+    /// Write the graph in the Graphviz DOT language, with external targets as dashed nodes and
+    /// tail calls as dashed edges
+    pub fn write_dot<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        writeln!(w, "digraph calls {{")?;
+        writeln!(w, "    node [shape=box, fontname=monospace];")?;
+
+        for function in self.functions.values() {
+            writeln!(
+                w,
+                "    {} [label=\"{}\\l0x{:0>4x}\\l\"];",
+                function_node(function.address),
+                dot::escape(&function.name),
+                function.address
+            )?;
+        }
+        let external: BTreeSet<u64> = self
+            .calls
+            .iter()
+            .filter_map(|call| match call.target {
+                CallTarget::External(address) => Some(address),
+                _ => None,
+            })
+            .collect();
+        for address in external {
+            writeln!(
+                w,
+                "    {} [style=dashed];",
+                helper::get_external_block_name(address)
+            )?;
+        }
+        if self
+            .calls
+            .iter()
+            .any(|call| call.target == CallTarget::Indirect)
+        {
+            writeln!(w, "    {INDIRECT_TARGET} [label=\"?\", shape=circle];")?;
+        }
+
+        for call in &self.calls {
+            let target = match call.target {
+                CallTarget::Function(address) => function_node(address),
+                CallTarget::External(address) => helper::get_external_block_name(address),
+                CallTarget::Indirect => INDIRECT_TARGET.to_string(),
+            };
+            writeln!(
+                w,
+                "    {} -> {} [label=\"0x{:0>4x}\"{}];",
+                function_node(call.caller),
+                target,
+                call.pc,
+                if call.kind == CallKind::TailCall {
+                    ", style=dashed"
+                } else {
+                    ""
+                }
+            )?;
+        }
+
+        writeln!(w, "}}")
+    }
+
+    /// Write the graph as JSON, with a `functions` array of names and address ranges and a
+    /// `calls` array of call sites
+    pub fn write_json<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        writeln!(w, "{{")?;
+        writeln!(w, "  \"functions\": [")?;
+        for (i, function) in self.functions.values().enumerate() {
+            let separator = if i + 1 < self.functions.len() {
+                ","
+            } else {
+                ""
+            };
+            writeln!(
+                w,
+                "    {{\"name\": \"{}\", \"address\": {}, \"end\": {}}}{separator}",
+                json_escape(&function.name),
+                function.address,
+                function.end
+            )?;
+        }
+        writeln!(w, "  ],")?;
+        writeln!(w, "  \"calls\": [")?;
+        for (i, call) in self.calls.iter().enumerate() {
+            let separator = if i + 1 < self.calls.len() { "," } else { "" };
+            let kind = match call.kind {
+                CallKind::Call => "call",
+                CallKind::TailCall => "tail_call",
+            };
+            let (target, address) = match call.target {
+                CallTarget::Function(address) => ("function", address.to_string()),
+                CallTarget::External(address) => ("external", address.to_string()),
+                CallTarget::Indirect => ("indirect", "null".to_string()),
+            };
+            writeln!(
+                w,
+                "    {{\"caller\": {}, \"pc\": {}, \"kind\": \"{kind}\", \"target\": \"{target}\", \
+                 \"address\": {address}}}{separator}",
+                call.caller, call.pc
+            )?;
+        }
+        writeln!(w, "  ]")?;
+        writeln!(w, "}}")
+    }
+
+    fn target(&self, address: u64) -> CallTarget {
+        match self.function_containing(address) {
+            Some(function) => CallTarget::Function(function.address),
+            None => CallTarget::External(address),
+        }
+    }
+}
+
+fn function_node(address: u64) -> String {
+    format!("function_{}", address)
+}
+
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
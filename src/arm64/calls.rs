@@ -0,0 +1,23 @@
+use tnj::air::instructions::{BasicBlock, Value};
+
+/// Function called by a call of a lifted code region
+#[derive(Debug, Clone, Copy)]
+pub enum Callee {
+    /// Target of `BL`
+    Direct(u64),
+    /// Target register of `BLR`, authenticated for `BLRAA` and its variants, as read before the
+    /// call
+    Indirect(Value),
+}
+
+/// Call of a lifted code region in [`CallMode::Call`](crate::arm64::CallMode::Call), where the
+/// callee is not followed
+#[derive(Debug, Clone)]
+pub struct LiftedCall {
+    /// Address of the call instruction
+    pub pc: u64,
+    /// Block ending in the call
+    pub block: BasicBlock,
+    /// Function the call transfers control to
+    pub callee: Callee,
+}
@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How control reaches the target of an edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Execution continues at the next instruction, including the not-taken side of a
+    /// conditional branch
+    Fallthrough,
+    /// Unconditional direct branch (`B`)
+    Branch,
+    /// Taken side of a conditional branch (`B.cond`, `CBZ`, `CBNZ`, `TBZ`, `TBNZ`)
+    Conditional,
+    /// Call of a function (`BL`, `BLR`); without a target for indirect calls
+    Call,
+    /// Return to the caller (`RET`); never has a target
+    Return,
+    /// Indirect branch (`BR`); has a target for each entry of a recovered jump table and none
+    /// otherwise
+    Dynamic,
+}
+
+/// Edge leaving a node of the control-flow graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CfgEdge {
+    /// Start address of the node the edge leaves
+    pub from: u64,
+    /// Target address, `None` if it is only known at runtime
+    pub to: Option<u64>,
+    /// How control reaches the target
+    pub kind: EdgeKind,
+}
+
+/// Basic block of the control-flow graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfgNode {
+    /// Address of the first instruction
+    pub start: u64,
+    /// Address after the last instruction
+    pub end: u64,
+    /// Edges leaving the node, including edges to targets outside the code
+    pub successors: Vec<CfgEdge>,
+    /// Edges entering the node from other nodes of the code
+    pub predecessors: Vec<CfgEdge>,
+}
+
+/// Control transfer of a single instruction, recorded while resolving labels
+#[derive(Debug, Clone)]
+pub(crate) struct Transfer {
+    /// Edges of the instruction apart from the fallthrough
+    pub(crate) edges: Vec<(Option<u64>, EdgeKind)>,
+    /// Whether execution may continue at the next instruction
+    pub(crate) falls_through: bool,
+}
+
+/// Control-flow graph of a code region, with nodes keyed by their start address
+#[derive(Debug, Clone, Default)]
+pub struct ControlFlowGraph {
+    nodes: BTreeMap<u64, CfgNode>,
+}
+
+impl ControlFlowGraph {
+    /// Build the graph of the code between `base_address` and `end_address` from the block
+    /// starts and the control transfers of its instructions. Every instruction that does not
+    /// fall through ends a node, even where the lifter keeps one block.
+    pub(crate) fn new(
+        base_address: u64,
+        end_address: u64,
+        block_starts: &BTreeSet<u64>,
+        transfers: &BTreeMap<u64, Transfer>,
+    ) -> Self {
+        const INSTRUCTION_SIZE: u64 = 4;
+        let in_code = |addr: &u64| (base_address..end_address).contains(addr);
+
+        let mut starts: BTreeSet<u64> = block_starts.iter().copied().filter(in_code).collect();
+        if base_address < end_address {
+            starts.insert(base_address);
+        }
+        starts.extend(
+            transfers
+                .iter()
+                .filter(|(_, transfer)| !transfer.falls_through)
+                .map(|(&addr, _)| addr + INSTRUCTION_SIZE)
+                .filter(in_code),
+        );
+
+        let mut nodes = BTreeMap::new();
+        let mut starts_iter = starts.iter().peekable();
+        while let Some(&start) = starts_iter.next() {
+            let end = starts_iter.peek().map_or(end_address, |&&next| next);
+            let mut successors = Vec::new();
+            let mut falls_through = true;
+            // a transfer ends its node, so it is the last instruction
+            if let Some((_, transfer)) = transfers.range(start..end).next() {
+                successors.extend(transfer.edges.iter().map(|&(to, kind)| CfgEdge {
+                    from: start,
+                    to,
+                    kind,
+                }));
+                falls_through = transfer.falls_through;
+            }
+            if falls_through && end < end_address {
+                successors.push(CfgEdge {
+                    from: start,
+                    to: Some(end),
+                    kind: EdgeKind::Fallthrough,
+                });
+            }
+            nodes.insert(
+                start,
+                CfgNode {
+                    start,
+                    end,
+                    successors,
+                    predecessors: Vec::new(),
+                },
+            );
+        }
+
+        let edges: Vec<CfgEdge> = nodes
+            .values()
+            .flat_map(|node| node.successors.iter().copied())
+            .collect();
+        for edge in edges {
+            if let Some(node) = edge.to.and_then(|to| nodes.get_mut(&to)) {
+                node.predecessors.push(edge);
+            }
+        }
+
+        Self { nodes }
+    }
+
+    /// Nodes sorted by start address
+    pub fn nodes(&self) -> impl Iterator<Item = &CfgNode> + '_ {
+        self.nodes.values()
+    }
+
+    /// Node starting at `start`
+    pub fn node(&self, start: u64) -> Option<&CfgNode> {
+        self.nodes.get(&start)
+    }
+
+    /// Node containing the instruction at `addr`
+    pub fn node_containing(&self, addr: u64) -> Option<&CfgNode> {
+        self.nodes
+            .range(..=addr)
+            .next_back()
+            .map(|(_, node)| node)
+            .filter(|node| addr < node.end)
+    }
+
+    /// All edges, sorted by the start address of the node they leave
+    pub fn edges(&self) -> impl Iterator<Item = &CfgEdge> + '_ {
+        self.nodes.values().flat_map(|node| node.successors.iter())
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
@@ -0,0 +1,255 @@
+use super::{helper, CfgEdge, CfgNode, ControlFlowGraph, EdgeKind};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use tnj::air::instructions::CodeRegion;
+
+/// Node that edges without a known target point to
+const UNKNOWN_TARGET: &str = "unknown";
+
+impl ControlFlowGraph {
+    /// Write the graph in the Graphviz DOT language, with each node labelled by its block name
+    /// and address range
+    pub fn write_dot<W>(&self, w: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        write_graph(w, self, |node| {
+            vec![format!("0x{:0>4x}..0x{:0>4x}", node.start, node.end)]
+        })
+    }
+}
+
+/// Write `cfg` as a DOT graph whose nodes are labelled by their block name followed by the lines
+/// returned by `lines`
+pub(crate) fn write_graph<W, F>(w: &mut W, cfg: &ControlFlowGraph, mut lines: F) -> io::Result<()>
+where
+    W: ?Sized + Write,
+    F: FnMut(&CfgNode) -> Vec<String>,
+{
+    writeln!(w, "digraph cfg {{")?;
+    writeln!(w, "    node [shape=box, fontname=monospace];")?;
+
+    for node in cfg.nodes() {
+        let mut label = format!("{}:\\l", helper::get_block_name(node.start));
+        for line in lines(node) {
+            label.push_str(&escape(&line));
+            label.push_str("\\l");
+        }
+        writeln!(
+            w,
+            "    {} [label=\"{}\"];",
+            helper::get_block_name(node.start),
+            label
+        )?;
+    }
+
+    let mut external = BTreeSet::new();
+    let mut unknown = false;
+    for edge in cfg.edges() {
+        match edge.to {
+            Some(to) if cfg.node(to).is_none() => {
+                external.insert(to);
+            }
+            None => unknown = true,
+            _ => {}
+        }
+    }
+    for target in external {
+        writeln!(
+            w,
+            "    {} [style=dashed];",
+            helper::get_external_block_name(target)
+        )?;
+    }
+    if unknown {
+        writeln!(w, "    {UNKNOWN_TARGET} [label=\"?\", shape=circle];")?;
+    }
+
+    for edge in cfg.edges() {
+        writeln!(
+            w,
+            "    {} -> {} [label=\"{}\"{}];",
+            helper::get_block_name(edge.from),
+            target_name(cfg, edge),
+            kind_name(edge.kind),
+            if edge.kind == EdgeKind::Fallthrough {
+                ", style=dashed"
+            } else {
+                ""
+            }
+        )?;
+    }
+
+    writeln!(w, "}}")
+}
+
+/// Write the blocks of a lifted code region as a DOT graph whose nodes are labelled by the AIR of
+/// their block, as printed by [`CodeRegion::display`], and whose edges are the targets of the
+/// `jump`, `jumpif` and `dynamic_jump` terminators
+pub(crate) fn write_code_region<W>(w: &mut W, code_region: &CodeRegion) -> io::Result<()>
+where
+    W: ?Sized + Write,
+{
+    let blocks = air_blocks(&code_region.display().to_string());
+
+    writeln!(w, "digraph cfg {{")?;
+    writeln!(w, "    node [shape=box, fontname=monospace];")?;
+
+    for block in &blocks {
+        let mut label = format!("{}:\\l", escape(&block.name));
+        for line in &block.lines {
+            label.push_str(&escape(line));
+            label.push_str("\\l");
+        }
+        writeln!(
+            w,
+            "    {} [label=\"{}\"{}];",
+            node_id(&block.name),
+            label,
+            if block.name.starts_with("external_") {
+                ", style=dashed"
+            } else {
+                ""
+            }
+        )?;
+    }
+
+    let edges: Vec<(&str, Option<&str>, &str)> = blocks
+        .iter()
+        .flat_map(|block| {
+            block.lines.iter().flat_map(move |line| {
+                terminator_targets(line)
+                    .into_iter()
+                    .map(move |(to, kind)| (block.name.as_str(), to, kind))
+            })
+        })
+        .collect();
+    if edges.iter().any(|(_, to, _)| to.is_none()) {
+        writeln!(w, "    {UNKNOWN_TARGET} [label=\"?\", shape=circle];")?;
+    }
+    for (from, to, kind) in edges {
+        writeln!(
+            w,
+            "    {} -> {} [label=\"{kind}\"];",
+            node_id(from),
+            to.map_or_else(|| UNKNOWN_TARGET.to_string(), node_id)
+        )?;
+    }
+
+    writeln!(w, "}}")
+}
+
+/// Block of the printed AIR of a code region
+struct AirBlock {
+    name: String,
+    lines: Vec<String>,
+}
+
+/// Splits printed AIR into blocks, which start at an unindented `name:` header and contain the
+/// indented lines after it
+fn air_blocks(air: &str) -> Vec<AirBlock> {
+    let mut blocks: Vec<AirBlock> = Vec::new();
+    for line in air.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((name, _)) = line.split_once(':') {
+                blocks.push(AirBlock {
+                    name: name.to_string(),
+                    lines: Vec::new(),
+                });
+            }
+        } else if let Some(block) = blocks.last_mut() {
+            block.lines.push(line.trim().to_string());
+        }
+    }
+    blocks
+}
+
+/// Blocks a printed AIR instruction branches to with the label of each edge, with `None` for the
+/// target of a dynamic jump, and nothing for other instructions
+fn terminator_targets(line: &str) -> Vec<(Option<&str>, &'static str)> {
+    let (op, operands) = line.split_once(' ').unwrap_or((line, ""));
+    let operands = split_operands(operands);
+    match (op, operands.as_slice()) {
+        ("jump", [target, ..]) => vec![(Some(block_name(target)), "jump")],
+        ("jumpif", [_, then, otherwise, ..]) => vec![
+            (Some(block_name(then)), "true"),
+            (Some(block_name(otherwise)), "false"),
+        ],
+        ("dynamic_jump", _) => vec![(None, "dynamic_jump")],
+        _ => Vec::new(),
+    }
+}
+
+/// Splits the operands of a printed instruction at the commas outside of block arguments
+fn split_operands(operands: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in operands.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(operands[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !operands[start..].trim().is_empty() {
+        split.push(operands[start..].trim());
+    }
+    split
+}
+
+/// Name of the block of a branch target, without its arguments
+fn block_name(target: &str) -> &str {
+    target.split('(').next().unwrap_or(target).trim()
+}
+
+/// DOT id of the node of a block, quoted unless the name is a plain identifier
+fn node_id(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", escape(name))
+    }
+}
+
+fn target_name(cfg: &ControlFlowGraph, edge: &CfgEdge) -> String {
+    match edge.to {
+        Some(to) if cfg.node(to).is_some() => helper::get_block_name(to),
+        Some(to) => helper::get_external_block_name(to),
+        None => UNKNOWN_TARGET.to_string(),
+    }
+}
+
+fn kind_name(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Fallthrough => "fallthrough",
+        EdgeKind::Branch => "branch",
+        EdgeKind::Conditional => "conditional",
+        EdgeKind::Call => "call",
+        EdgeKind::Return => "return",
+        EdgeKind::Dynamic => "dynamic",
+    }
+}
+
+/// Escape a line for a quoted DOT label
+pub(crate) fn escape(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\t' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,192 @@
+/// `DW_EH_PE_omit`, no value is present
+const PE_OMIT: u8 = 0xff;
+/// `DW_EH_PE_pcrel`, the value is relative to its own address
+const PE_PCREL: u8 = 0x10;
+
+/// Address ranges `(start, end)` of the FDEs in an `.eh_frame` section loaded at `address`.
+///
+/// Parsing stops at the zero terminator or the first malformed entry, keeping the ranges found
+/// before it, since the section only serves as a hint for function boundaries.
+pub(crate) fn fde_ranges(address: u64, bytes: &[u8]) -> Vec<(u64, u64)> {
+    let mut ranges = Vec::new();
+    let mut reader = Reader {
+        bytes,
+        address,
+        offset: 0,
+    };
+    while let Some(range) = reader.next_entry() {
+        ranges.extend(range);
+    }
+    ranges
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// Virtual address of `bytes[0]`
+    address: u64,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Parses the entry at the current offset, returning `Some(None)` for a CIE and `None` at the
+    /// end of the section
+    fn next_entry(&mut self) -> Option<Option<(u64, u64)>> {
+        let length = match self.u32()? {
+            0 => return None,
+            0xffff_ffff => usize::try_from(self.u64()?).ok()?,
+            length => length as usize,
+        };
+        let start = self.offset;
+        let end = start.checked_add(length)?;
+        if end > self.bytes.len() {
+            return None;
+        }
+
+        let id_offset = self.offset;
+        let cie_pointer = self.u32()?;
+        let range = if cie_pointer == 0 {
+            None
+        } else {
+            let cie_offset = id_offset.checked_sub(cie_pointer as usize)?;
+            let encoding = self.clone_at(cie_offset).fde_encoding()?;
+            let pc_begin = self.pointer(encoding)?;
+            let pc_range = self.pointer(encoding & 0x0f)?;
+            Some((pc_begin, pc_begin.checked_add(pc_range)?))
+        };
+
+        self.offset = end;
+        Some(range)
+    }
+
+    fn clone_at(&self, offset: usize) -> Self {
+        Self {
+            bytes: self.bytes,
+            address: self.address,
+            offset,
+        }
+    }
+
+    /// Pointer encoding of the FDEs of the CIE at the current offset, from its `R` augmentation
+    fn fde_encoding(&mut self) -> Option<u8> {
+        if self.u32()? == 0xffff_ffff {
+            self.u64()?;
+        }
+        if self.u32()? != 0 {
+            return None;
+        }
+        let version = self.u8()?;
+        let augmentation = self.string()?;
+        self.uleb128()?; // code alignment
+        self.sleb128()?; // data alignment
+        if version == 1 {
+            self.u8()?;
+        } else {
+            self.uleb128()?;
+        }
+
+        let mut augmentation = augmentation.iter();
+        if augmentation.next() != Some(&b'z') {
+            // without augmentation data, pointers are absolute
+            return Some(0);
+        }
+        self.uleb128()?;
+        for c in augmentation {
+            match c {
+                b'R' => return Some(self.u8()?),
+                b'P' => {
+                    let encoding = self.u8()?;
+                    self.pointer(encoding)?;
+                }
+                b'L' => {
+                    self.u8()?;
+                }
+                _ => {}
+            }
+        }
+        Some(0)
+    }
+
+    fn pointer(&mut self, encoding: u8) -> Option<u64> {
+        if encoding == PE_OMIT {
+            return None;
+        }
+        let field_address = self.address.checked_add(self.offset as u64)?;
+        let value = match encoding & 0x0f {
+            0x00 | 0x04 | 0x0c => self.u64()?,
+            0x01 => self.uleb128()?,
+            0x02 => self.u16()? as u64,
+            0x03 => self.u32()? as u64,
+            0x09 => self.sleb128()? as u64,
+            0x0a => self.u16()? as i16 as u64,
+            0x0b => self.u32()? as i32 as u64,
+            _ => return None,
+        };
+        match encoding & 0x70 {
+            0 => Some(value),
+            PE_PCREL => Some(field_address.wrapping_add(value)),
+            _ => None,
+        }
+    }
+
+    fn take(&mut self, size: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset.checked_add(size)?)?;
+        self.offset += size;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let len = self
+            .bytes
+            .get(self.offset..)?
+            .iter()
+            .position(|&b| b == 0)?;
+        let string = self.take(len)?;
+        self.offset += 1;
+        Some(string)
+    }
+
+    fn uleb128(&mut self) -> Option<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn sleb128(&mut self) -> Option<i64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as i64) << shift;
+            if byte & 0x80 == 0 {
+                if shift + 7 < 64 && byte & 0x40 != 0 {
+                    value |= -1i64 << (shift + 7);
+                }
+                return Some(value);
+            }
+        }
+        None
+    }
+}
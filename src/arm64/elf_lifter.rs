@@ -0,0 +1,274 @@
+use crate::arm64::{
+    AArch64Lifter, AArch64LifterError, CallGraph, DataRegion, FunctionDiscovery, LiftReport,
+};
+use crate::Lifter;
+use elf::abi::{EM_AARCH64, STT_FUNC};
+use elf::endian::AnyEndian;
+use elf::parse::ParseError;
+use elf::ElfBytes;
+use thiserror::Error;
+use tnj::air::instructions::CodeRegion;
+
+/// A function inside the `.text` section of an ELF file
+#[derive(Debug, Clone)]
+pub struct ElfFunction<'a> {
+    /// Symbol name, or `sub_<address>` for functions found without symbols
+    pub name: String,
+    /// Names of further function symbols at the same address
+    pub aliases: Vec<String>,
+    /// Virtual address of the first instruction
+    pub address: u64,
+    /// Machine code of the function
+    pub code: &'a [u8],
+}
+
+/// A function lifted from an ELF file
+pub struct LiftedFunction {
+    /// Symbol name
+    pub name: String,
+    /// Virtual address of the first instruction
+    pub address: u64,
+    /// Lifted code
+    pub code_region: CodeRegion,
+    /// Instructions that were lifted to opaque values, skipped or ignored
+    pub report: LiftReport,
+}
+
+/// Lifts AArch64 ELF executables and shared objects function by function
+pub struct AArch64ElfLifter<'a> {
+    functions: Vec<ElfFunction<'a>>,
+    skipped: Vec<AArch64ElfError>,
+    data_regions: Vec<DataRegion<'a>>,
+}
+
+impl<'a> AArch64ElfLifter<'a> {
+    /// Parse an ELF file and split its `.text` section by `STT_FUNC` symbols.
+    ///
+    /// The static symbol table is used if present, otherwise the dynamic symbol table. Symbols
+    /// without a size extend to the next function or the end of `.text`, and functions that do not
+    /// end on a 4-byte boundary are truncated to their last whole instruction. Symbols at the same
+    /// address become a single function whose first symbol gives the name. Symbols that are not
+    /// 4-byte aligned, or functions without a whole instruction, are skipped and reported by
+    /// [`AArch64ElfLifter::skipped`]. Jump tables are read from `.text` and `.rodata`.
+    pub fn new(data: &'a [u8]) -> Result<Self, AArch64ElfError> {
+        let file = Self::parse(data)?;
+        let (text_start, text, data_regions) = Self::sections(&file)?;
+        let text_end = text_start + text.len() as u64;
+
+        let (symbols, strings) = match file.symbol_table()? {
+            Some(table) => table,
+            None => file
+                .dynamic_symbol_table()?
+                .ok_or(AArch64ElfError::MissingSymbolTable)?,
+        };
+
+        let mut symbols = symbols
+            .iter()
+            .filter(|s| s.st_symtype() == STT_FUNC && !s.is_undefined())
+            .filter(|s| (text_start..text_end).contains(&s.st_value))
+            .map(|s| Ok((strings.get(s.st_name as usize)?.to_string(), s)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        symbols.sort_by_key(|(_, s)| s.st_value);
+
+        let mut skipped = Vec::new();
+        symbols.retain(|(name, s)| {
+            let aligned = s.st_value % 4 == 0;
+            if !aligned {
+                skipped.push(AArch64ElfError::Unaligned(name.clone()));
+            }
+            aligned
+        });
+
+        // aliases share the address, and the largest size, of their first symbol
+        let mut groups: Vec<(u64, u64, Vec<String>)> = Vec::with_capacity(symbols.len());
+        for (name, symbol) in symbols {
+            match groups.last_mut() {
+                Some((address, size, names)) if *address == symbol.st_value => {
+                    *size = (*size).max(symbol.st_size);
+                    names.push(name);
+                }
+                _ => groups.push((symbol.st_value, symbol.st_size, vec![name])),
+            }
+        }
+
+        let mut functions = Vec::with_capacity(groups.len());
+        for (i, &(start, size, ref names)) in groups.iter().enumerate() {
+            let end = if size != 0 {
+                start.saturating_add(*size).min(text_end)
+            } else {
+                groups
+                    .get(i + 1)
+                    .map_or(text_end, |(address, _, _)| *address)
+            };
+            // trailing bytes that do not form a whole instruction are not code
+            let end = end - end % 4;
+
+            let mut names = names.iter().cloned();
+            let name = names.next().expect("group to have a symbol");
+            if end <= start {
+                skipped.push(AArch64ElfError::Unaligned(name));
+                continue;
+            }
+
+            let offset = (start - text_start) as usize;
+            let size = (end - start) as usize;
+            functions.push(ElfFunction {
+                name,
+                aliases: names.collect(),
+                address: start,
+                code: &text[offset..offset + size],
+            });
+        }
+
+        Ok(Self {
+            functions,
+            skipped,
+            data_regions,
+        })
+    }
+
+    /// Parse an ELF file without relying on symbols and split its `.text` section by the
+    /// functions found by [`FunctionDiscovery`], using the ELF entry point and the FDEs of
+    /// `.eh_frame` if present.
+    ///
+    /// Functions are named `sub_<address>` after their hexadecimal address.
+    pub fn discover(data: &'a [u8]) -> Result<Self, AArch64ElfError> {
+        let file = Self::parse(data)?;
+        let (text_start, text, data_regions) = Self::sections(&file)?;
+
+        let mut discovery =
+            FunctionDiscovery::new(text, text_start).with_entry_point(file.ehdr.e_entry);
+        if let Some(eh_frame_shdr) = file.section_header_by_name(".eh_frame")? {
+            let (eh_frame, _) = file.section_data(&eh_frame_shdr)?;
+            discovery = discovery.with_eh_frame(eh_frame_shdr.sh_addr, eh_frame);
+        }
+
+        let functions = discovery
+            .discover()
+            .into_iter()
+            .map(|function| ElfFunction {
+                name: format!("sub_{:x}", function.address),
+                aliases: Vec::new(),
+                address: function.address,
+                code: function.code,
+            })
+            .collect();
+
+        Ok(Self {
+            functions,
+            skipped: Vec::new(),
+            data_regions,
+        })
+    }
+
+    fn parse(data: &'a [u8]) -> Result<ElfBytes<'a, AnyEndian>, AArch64ElfError> {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(data)?;
+
+        if file.ehdr.e_machine != EM_AARCH64 {
+            return Err(AArch64ElfError::UnsupportedMachine(file.ehdr.e_machine));
+        }
+
+        Ok(file)
+    }
+
+    /// Address and contents of `.text`, and the regions jump tables are read from
+    fn sections(
+        file: &ElfBytes<'a, AnyEndian>,
+    ) -> Result<(u64, &'a [u8], Vec<DataRegion<'a>>), AArch64ElfError> {
+        let text_shdr = file
+            .section_header_by_name(".text")?
+            .ok_or(AArch64ElfError::MissingSection(".text"))?;
+        let (text, _) = file.section_data(&text_shdr)?;
+        let text_start = text_shdr.sh_addr;
+
+        let mut data_regions = vec![DataRegion::new(text_start, text)];
+        if let Some(rodata_shdr) = file.section_header_by_name(".rodata")? {
+            let (rodata, _) = file.section_data(&rodata_shdr)?;
+            data_regions.push(DataRegion::new(rodata_shdr.sh_addr, rodata));
+        }
+
+        Ok((text_start, text, data_regions))
+    }
+
+    /// Functions found in the `.text` section, sorted by address
+    pub fn functions(&self) -> &[ElfFunction<'a>] {
+        &self.functions
+    }
+
+    /// Function symbols that were skipped, with the reason, such as
+    /// [`AArch64ElfError::Unaligned`]
+    pub fn skipped(&self) -> &[AArch64ElfError] {
+        &self.skipped
+    }
+
+    /// Call graph of the functions
+    pub fn call_graph(&self) -> CallGraph {
+        CallGraph::new(&self.functions)
+    }
+
+    /// Lift a single function at its virtual address
+    pub fn lift_function(
+        &self,
+        function: &ElfFunction,
+    ) -> Result<(CodeRegion, LiftReport), AArch64LifterError> {
+        let mut lifter = AArch64Lifter::new(function.code, &[]).with_base_address(function.address);
+        for &region in &self.data_regions {
+            lifter = lifter.with_data_region(region);
+        }
+        lifter.lift_with_report()
+    }
+
+    /// Lift all functions, stopping at the first one that fails
+    pub fn lift(&self) -> Result<Vec<LiftedFunction>, AArch64ElfError> {
+        self.functions
+            .iter()
+            .map(|function| {
+                let (code_region, report) =
+                    self.lift_function(function)
+                        .map_err(|source| AArch64ElfError::Lifter {
+                            name: function.name.clone(),
+                            source,
+                        })?;
+                Ok(LiftedFunction {
+                    name: function.name.clone(),
+                    address: function.address,
+                    code_region,
+                    report,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Error type for lifting ELF files
+#[derive(Debug, Error)]
+pub enum AArch64ElfError {
+    /// Error parsing the ELF file
+    #[error("Error parsing ELF file: {0}")]
+    Parse(#[from] ParseError),
+
+    /// The ELF file is not an AArch64 binary
+    #[error("Unsupported machine type: {0}")]
+    UnsupportedMachine(u16),
+
+    /// A required section is missing
+    #[error("Missing {0} section")]
+    MissingSection(&'static str),
+
+    /// Neither a static nor a dynamic symbol table is present
+    #[error("Missing symbol table")]
+    MissingSymbolTable,
+
+    /// A function symbol is not 4-byte aligned, or its function has no whole instruction
+    #[error("Function {0} is not 4-byte aligned")]
+    Unaligned(String),
+
+    /// Error lifting a function
+    #[error("Error lifting function {name}: {source}")]
+    Lifter {
+        /// Symbol name
+        name: String,
+        /// Lifter error
+        source: AArch64LifterError,
+    },
+}
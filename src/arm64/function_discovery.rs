@@ -0,0 +1,216 @@
+use super::{eh_frame, helper};
+use std::collections::{BTreeMap, BTreeSet};
+use yaxpeax_arch::{Arch, Decoder, U8Reader};
+use yaxpeax_arm::armv8::a64::{ARMv8, Instruction, Opcode, Operand};
+
+const INSTRUCTION_SIZE: u64 = 4;
+
+/// `BTI c`, the landing pad for calls
+const BTI_C: u32 = 0x22;
+/// `PACIASP` and `PACIBSP`
+const PAC_SP: [u32; 2] = [0x19, 0x1b];
+/// `NOP`, used to pad functions to an alignment
+const NOP: u32 = 0x00;
+
+/// Evidence for a function starting at an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FunctionSource {
+    /// The entry point of the executable
+    EntryPoint,
+    /// The start of an FDE in `.eh_frame`
+    FrameDescription,
+    /// The target of a `BL`
+    CallTarget,
+    /// A prologue: `BTI c`, `PACIASP` or `PACIBSP`, or a frame record pushed with
+    /// `stp x29, x30, [sp, #-N]!` right after the end of the preceding code
+    Prologue,
+}
+
+/// Address range of a function found by [`FunctionDiscovery`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredFunction<'a> {
+    /// Virtual address of the first instruction
+    pub address: u64,
+    /// Machine code of the function
+    pub code: &'a [u8],
+    /// Why the address was taken as the start of a function
+    pub sources: BTreeSet<FunctionSource>,
+}
+
+impl DiscoveredFunction<'_> {
+    /// Address after the last instruction
+    pub fn end(&self) -> u64 {
+        self.address + self.code.len() as u64
+    }
+}
+
+/// Finds function boundaries in code without symbols, such as the `.text` section of a stripped
+/// binary.
+///
+/// Functions start at the entry point, FDE starts, `BL` targets and prologues inside the code.
+/// Each extends to the end of its FDE or to the next function, whichever comes first, so the
+/// returned functions can be lifted one at a time with
+/// [`AArch64Lifter`](crate::arm64::AArch64Lifter).
+pub struct FunctionDiscovery<'a> {
+    code: &'a [u8],
+    base_address: u64,
+    entry_points: Vec<u64>,
+    frames: BTreeMap<u64, u64>,
+}
+
+impl<'a> FunctionDiscovery<'a> {
+    /// Create a discovery pass over `code` loaded at `base_address`
+    pub fn new(code: &'a [u8], base_address: u64) -> Self {
+        Self {
+            code,
+            base_address,
+            entry_points: Vec::new(),
+            frames: BTreeMap::new(),
+        }
+    }
+
+    /// Add a known entry point, such as the ELF entry point
+    pub fn with_entry_point(mut self, address: u64) -> Self {
+        self.entry_points.push(address);
+        self
+    }
+
+    /// Add the function ranges described by the FDEs of an `.eh_frame` section loaded at
+    /// `address`. Parsing stops at the first malformed entry.
+    pub fn with_eh_frame(mut self, address: u64, bytes: &[u8]) -> Self {
+        for (start, end) in eh_frame::fde_ranges(address, bytes) {
+            self.frames.entry(start).or_insert(end);
+        }
+        self
+    }
+
+    /// Functions found in the code, sorted by address
+    pub fn discover(&self) -> Vec<DiscoveredFunction<'a>> {
+        let mut starts: BTreeMap<u64, BTreeSet<FunctionSource>> = BTreeMap::new();
+        let mut add = |address: u64, source| {
+            if self.contains(address) {
+                starts.entry(address).or_default().insert(source);
+            }
+        };
+        for &address in &self.entry_points {
+            add(address, FunctionSource::EntryPoint);
+        }
+        for &start in self.frames.keys() {
+            add(start, FunctionSource::FrameDescription);
+        }
+
+        let decoder = <ARMv8 as Arch>::Decoder::default();
+        let mut prev: Option<Instruction> = None;
+        for (i, word) in self
+            .code
+            .chunks_exact(INSTRUCTION_SIZE as usize)
+            .enumerate()
+        {
+            let address = self.base_address + i as u64 * INSTRUCTION_SIZE;
+            // words that do not decode are data, such as literal pools, and end the code before
+            let Ok(inst) = decoder.decode(&mut U8Reader::new(word)) else {
+                prev = None;
+                continue;
+            };
+            if inst.opcode == Opcode::BL {
+                if let Some(offset) = helper::get_pc_offset_as_int(inst.operands[0]) {
+                    add(
+                        address.wrapping_add(offset as u64),
+                        FunctionSource::CallTarget,
+                    );
+                }
+            }
+            if is_prologue(prev, inst) {
+                add(address, FunctionSource::Prologue);
+            }
+            prev = Some(inst);
+        }
+
+        let code_end = self.base_address + self.code.len() as u64;
+        let mut functions = Vec::with_capacity(starts.len());
+        let mut starts = starts.into_iter().peekable();
+        while let Some((address, sources)) = starts.next() {
+            let next = starts.peek().map_or(code_end, |&(next, _)| next);
+            let end = match self.frames.get(&address) {
+                Some(&frame_end) if frame_end > address && frame_end % INSTRUCTION_SIZE == 0 => {
+                    frame_end.min(next)
+                }
+                _ => next,
+            };
+            let offset = (address - self.base_address) as usize;
+            let size = (end - address) as usize;
+            functions.push(DiscoveredFunction {
+                address,
+                code: &self.code[offset..offset + size],
+                sources,
+            });
+        }
+        functions
+    }
+
+    fn contains(&self, address: u64) -> bool {
+        let size = self.code.len() as u64 / INSTRUCTION_SIZE * INSTRUCTION_SIZE;
+        address % INSTRUCTION_SIZE == 0
+            && address >= self.base_address
+            && address - self.base_address < size
+    }
+}
+
+/// Whether `inst` starts a function, given the instruction before it. A `BTI c` or a PAC
+/// instruction signing the return address is only found at function starts, but is part of the
+/// prologue after a `BTI c`. Pushing the frame record is only taken as a start where the preceding
+/// instruction cannot fall through into it.
+fn is_prologue(prev: Option<Instruction>, inst: Instruction) -> bool {
+    let prev_hint = prev.and_then(helper::get_hint_immediate);
+    match helper::get_hint_immediate(inst) {
+        Some(BTI_C) => return true,
+        Some(hint) if PAC_SP.contains(&hint) => return prev_hint != Some(BTI_C),
+        _ => {}
+    }
+    if !pushes_frame_record(inst) {
+        return false;
+    }
+    match prev {
+        None => true,
+        Some(prev) => match prev_hint {
+            Some(BTI_C) => false,
+            Some(hint) if PAC_SP.contains(&hint) => false,
+            Some(NOP) => true,
+            _ => !falls_through(prev),
+        },
+    }
+}
+
+/// `stp x29, x30, [sp, #-N]!`
+fn pushes_frame_record(inst: Instruction) -> bool {
+    matches!(
+        (inst.opcode, inst.operands),
+        (
+            Opcode::STP,
+            [
+                Operand::Register(_, 29),
+                Operand::Register(_, 30),
+                Operand::RegPreIndex(31, offset, true),
+                ..
+            ]
+        ) if offset < 0
+    )
+}
+
+fn falls_through(inst: Instruction) -> bool {
+    !matches!(
+        inst.opcode,
+        Opcode::B
+            | Opcode::BR
+            | Opcode::BRAA
+            | Opcode::BRAAZ
+            | Opcode::BRAB
+            | Opcode::BRABZ
+            | Opcode::RET
+            | Opcode::RETAA
+            | Opcode::RETAB
+            | Opcode::BRK
+            | Opcode::HLT
+            | Opcode::UDF
+    )
+}
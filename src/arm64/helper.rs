@@ -1,11 +1,11 @@
 use tnj::types::{Type, I128, I16, I32, I64, I8};
-use yaxpeax_arm::armv8::a64::Instruction;
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode};
 use yaxpeax_arm::armv8::a64::{Operand, SIMDSizeCode, SizeCode};
 
-pub fn get_pc_offset_as_int(operand: Operand) -> i64 {
+pub fn get_pc_offset_as_int(operand: Operand) -> Option<i64> {
     match operand {
-        Operand::PCOffset(imm) => imm,
-        op => unimplemented!("dst op {:?}", op),
+        Operand::PCOffset(imm) => Some(imm),
+        _ => None,
     }
 }
 
@@ -13,7 +13,33 @@ pub fn get_block_name(jump_address: u64) -> String {
     format!("block_{}", jump_address)
 }
 
-pub fn get_type_by_inst(inst: Instruction) -> Type {
+pub fn get_external_block_name(jump_address: u64) -> String {
+    format!("external_{}", jump_address)
+}
+
+/// Immediate of a `HINT`, the `CRm:op2` fields selecting the hint
+pub fn get_hint_immediate(inst: Instruction) -> Option<u32> {
+    match (inst.opcode, inst.operands[0]) {
+        (Opcode::HINT, Operand::Immediate(imm)) => Some(imm),
+        _ => None,
+    }
+}
+
+/// Whether `inst` is a `BTI c`, `BTI j` or `BTI jc` landing pad for indirect branches
+pub fn is_landing_pad(inst: Instruction) -> bool {
+    matches!(get_hint_immediate(inst), Some(0x22 | 0x24 | 0x26))
+}
+
+/// Whether `inst` is a hint without effect on registers or memory: `NOP`, `YIELD`, `WFE`, `WFI`,
+/// `SEV`, `SEVL`, `DGH`, `ESB`, `PSB CSYNC`, `TSB CSYNC`, `CSDB` and `BTI` without targets
+pub fn is_nop_hint(inst: Instruction) -> bool {
+    matches!(
+        get_hint_immediate(inst),
+        Some(0x00..=0x06 | 0x10..=0x12 | 0x14 | 0x20)
+    )
+}
+
+pub fn get_type_by_inst(inst: Instruction) -> Option<Type> {
     get_type_by_operand(inst.operands[0])
 }
 
@@ -24,8 +50,8 @@ pub fn is_operand_general_purpose(op: Operand) -> bool {
     )
 }
 
-pub fn get_type_by_operand(op: Operand) -> Type {
-    match op {
+pub fn get_type_by_operand(op: Operand) -> Option<Type> {
+    let ty = match op {
         Operand::Register(sz, _) => get_type_by_sizecode(sz),
         Operand::RegisterOrSP(sz, _) => get_type_by_sizecode(sz),
         Operand::RegisterPair(sz, _) => get_type_by_sizecode(sz),
@@ -35,8 +61,9 @@ pub fn get_type_by_operand(op: Operand) -> Type {
         Operand::SIMDRegisterElementsMultipleLane(sz, _, _, _, _) => get_type_by_simd_sizecode(sz),
         Operand::SIMDRegisterGroup(sz, _, _, _) => get_type_by_simd_sizecode(sz),
         Operand::SIMDRegisterGroupLane(_, sz, _, _) => get_type_by_simd_sizecode(sz),
-        op => unimplemented!("Destination operand invalid {:?}", op),
-    }
+        _ => return None,
+    };
+    Some(ty)
 }
 
 pub fn get_type_by_sizecode(sz: SizeCode) -> Type {
@@ -0,0 +1,280 @@
+use std::collections::VecDeque;
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, ShiftStyle};
+
+/// Number of instructions before a `BR` searched for the jump-table idiom
+const WINDOW: usize = 12;
+
+/// Largest number of entries accepted for a recovered jump table
+const MAX_ENTRIES: u64 = 4096;
+
+/// `B.HI`, taken for an unsigned index greater than the bound
+const COND_HI: u8 = 8;
+/// `B.HS`, taken for an unsigned index greater or equal to the bound
+const COND_HS: u8 = 2;
+
+/// Read-only memory the lifter may read jump tables from, such as `.rodata`
+#[derive(Debug, Clone, Copy)]
+pub struct DataRegion<'a> {
+    /// Virtual address of the first byte
+    pub address: u64,
+    /// Contents of the region
+    pub bytes: &'a [u8],
+}
+
+impl<'a> DataRegion<'a> {
+    /// Create a region of `bytes` loaded at `address`
+    pub fn new(address: u64, bytes: &'a [u8]) -> Self {
+        Self { address, bytes }
+    }
+
+    fn read(&self, address: u64, size: usize) -> Option<&'a [u8]> {
+        let offset = usize::try_from(address.checked_sub(self.address)?).ok()?;
+        self.bytes.get(offset..offset.checked_add(size)?)
+    }
+}
+
+/// Jump table of a `BR` recovered from the surrounding code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpTable {
+    /// Address of the `BR`
+    pub branch: u64,
+    /// Address of the first entry
+    pub table: u64,
+    /// Target of each entry, in table order
+    pub targets: Vec<u64>,
+}
+
+/// Entry type of a jump table, from the load that reads it
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    size: usize,
+    signed: bool,
+}
+
+impl Entry {
+    fn of_load(opcode: Opcode) -> Option<Self> {
+        let (size, signed) = match opcode {
+            Opcode::LDRB => (1, false),
+            Opcode::LDRSB => (1, true),
+            Opcode::LDRH => (2, false),
+            Opcode::LDRSH => (2, true),
+            Opcode::LDR => (4, false),
+            Opcode::LDRSW => (4, true),
+            _ => return None,
+        };
+        Some(Self { size, signed })
+    }
+
+    fn decode(&self, bytes: &[u8]) -> i64 {
+        let mut buf = [0; 8];
+        buf[..self.size].copy_from_slice(bytes);
+        let val = u64::from_le_bytes(buf);
+        let unused = 64 - 8 * self.size as u32;
+        if self.signed {
+            ((val << unused) as i64) >> unused
+        } else {
+            val as i64
+        }
+    }
+}
+
+/// Recognises the jump-table idioms compilers emit for switch statements:
+///
+/// ```text
+/// cmp   w0, #N
+/// b.hi  default
+/// adrp  x9, table
+/// add   x9, x9, :lo12:table
+/// ldrsw x10, [x9, x0, lsl #2]     // or ldrb/ldrh for compressed tables
+/// add   x9, x9, x10               // or a separate adr base, optionally shifted
+/// br    x9
+/// ```
+pub(crate) struct JumpTableMatcher {
+    window: VecDeque<(u64, Instruction)>,
+}
+
+impl JumpTableMatcher {
+    pub(crate) fn new() -> Self {
+        Self {
+            window: VecDeque::with_capacity(WINDOW),
+        }
+    }
+
+    /// Remembers an instruction that is not a `BR`; code after an unconditional branch starts a
+    /// new window since it is not reached from the instructions before
+    pub(crate) fn push(&mut self, address: u64, inst: Instruction) {
+        if matches!(
+            inst.opcode,
+            Opcode::B | Opcode::BR | Opcode::BLR | Opcode::BL | Opcode::RET
+        ) {
+            self.window.clear();
+            return;
+        }
+        if self.window.len() == WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back((address, inst));
+    }
+
+    /// Jump table of the `BR` at `address`, if the preceding instructions match an idiom and
+    /// every entry can be read from `regions`
+    pub(crate) fn match_branch(
+        &self,
+        address: u64,
+        inst: Instruction,
+        regions: &[DataRegion],
+    ) -> Option<JumpTable> {
+        let Operand::Register(_, target_reg) = inst.operands[0] else {
+            return None;
+        };
+        let end = self.window.len();
+        let (add_index, add) = self.definition(target_reg, end)?;
+        if add.opcode != Opcode::ADD {
+            return None;
+        }
+        let (Operand::Register(_, lhs) | Operand::RegisterOrSP(_, lhs)) = add.operands[1] else {
+            return None;
+        };
+        let (rhs, shift) = match add.operands[2] {
+            Operand::Register(_, reg) => (reg, 0),
+            Operand::RegShift(ShiftStyle::LSL | ShiftStyle::SXTW | ShiftStyle::UXTW, s, _, reg) => {
+                (reg, s)
+            }
+            _ => return None,
+        };
+
+        let (base, load_index, load) = match self.table_load(rhs, add_index) {
+            Some((load_index, load)) => (self.constant(lhs, add_index)?, load_index, load),
+            None if shift == 0 => {
+                let (load_index, load) = self.table_load(lhs, add_index)?;
+                (self.constant(rhs, add_index)?, load_index, load)
+            }
+            None => return None,
+        };
+        let entry = Entry::of_load(load.opcode)?;
+        let Operand::RegRegOffset(table_reg, index_reg, ..) = load.operands[1] else {
+            return None;
+        };
+        let table = self.constant(table_reg, load_index)?;
+        let count = self.bound(index_reg, load_index)?;
+
+        let mut targets = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let entry_address = table.checked_add(i * entry.size as u64)?;
+            let bytes = regions
+                .iter()
+                .find_map(|region| region.read(entry_address, entry.size))?;
+            let offset = entry.decode(bytes) << shift;
+            let target = base.wrapping_add(offset as u64);
+            if target % 4 != 0 {
+                return None;
+            }
+            targets.push(target);
+        }
+
+        Some(JumpTable {
+            branch: address,
+            table,
+            targets,
+        })
+    }
+
+    /// Latest instruction before window index `end` that writes `reg`
+    fn definition(&self, reg: u16, end: usize) -> Option<(usize, Instruction)> {
+        (0..end)
+            .rev()
+            .map(|i| (i, self.window[i].1))
+            .find(|(_, inst)| {
+                matches!(
+                    inst.operands[0],
+                    Operand::Register(_, r) | Operand::RegisterOrSP(_, r) if r == reg
+                )
+            })
+    }
+
+    /// Load of a table entry into `reg` from `[table, index]`
+    fn table_load(&self, reg: u16, end: usize) -> Option<(usize, Instruction)> {
+        let (index, inst) = self.definition(reg, end)?;
+        let is_table_load = Entry::of_load(inst.opcode).is_some()
+            && matches!(inst.operands[1], Operand::RegRegOffset(..));
+        is_table_load.then_some((index, inst))
+    }
+
+    /// Address in `reg` set by `ADR`, or by `ADRP` and an `ADD` of the page offset
+    fn constant(&self, reg: u16, end: usize) -> Option<u64> {
+        let (index, inst) = self.definition(reg, end)?;
+        let address = self.window[index].0;
+        match (inst.opcode, inst.operands[1], inst.operands[2]) {
+            (Opcode::ADR, Operand::PCOffset(offset), _) => {
+                Some(address.wrapping_add(offset as u64))
+            }
+            (Opcode::ADRP, Operand::PCOffset(offset), _) => {
+                Some((address & !0xfff).wrapping_add(offset as u64))
+            }
+            (
+                Opcode::ADD,
+                Operand::Register(_, src) | Operand::RegisterOrSP(_, src),
+                Operand::Immediate(imm),
+            ) => Some(self.constant(src, index)?.wrapping_add(imm as u64)),
+            (
+                Opcode::ADD,
+                Operand::Register(_, src) | Operand::RegisterOrSP(_, src),
+                Operand::ImmShift(imm, s),
+            ) => Some(self.constant(src, index)?.wrapping_add((imm as u64) << s)),
+            _ => None,
+        }
+    }
+
+    /// Number of entries from the bounds check of `index_reg`: a `CMP` with an immediate followed
+    /// by a `B.HI` or `B.HS` to the default case. The index may have been copied to another
+    /// register after the check.
+    fn bound(&self, mut index_reg: u16, end: usize) -> Option<u64> {
+        let mut cmp = None;
+        for i in (0..end).rev() {
+            let inst = self.window[i].1;
+            match (inst.opcode, inst.operands) {
+                (Opcode::SUBS, [Operand::Register(_, 31), src, imm, ..])
+                    if source_register(src) == Some(index_reg) =>
+                {
+                    let limit = match imm {
+                        Operand::Immediate(imm) => imm as u64,
+                        Operand::ImmShift(imm, s) => (imm as u64) << s,
+                        _ => return None,
+                    };
+                    cmp = Some((i, limit));
+                    break;
+                }
+                // mov
+                (Opcode::ORR, [Operand::Register(_, dst), Operand::Register(_, 31), src, ..])
+                    if dst == index_reg =>
+                {
+                    index_reg = source_register(src)?;
+                }
+                (_, [Operand::Register(_, dst) | Operand::RegisterOrSP(_, dst), ..])
+                    if dst == index_reg =>
+                {
+                    return None
+                }
+                _ => {}
+            }
+        }
+        let (cmp_index, limit) = cmp?;
+        let count = (cmp_index + 1..end).find_map(|i| match self.window[i].1.opcode {
+            Opcode::Bcc(COND_HI) => Some(limit + 1),
+            Opcode::Bcc(COND_HS) => Some(limit),
+            _ => None,
+        })?;
+        (1..=MAX_ENTRIES).contains(&count).then_some(count)
+    }
+}
+
+/// Register read by a source operand: `cmp` reads `Rn` as a register that may be SP, and `mov`
+/// reads an unshifted register operand
+fn source_register(operand: Operand) -> Option<u16> {
+    match operand {
+        Operand::Register(_, reg)
+        | Operand::RegisterOrSP(_, reg)
+        | Operand::RegShift(ShiftStyle::LSL, 0, _, reg) => Some(reg),
+        _ => None,
+    }
+}
@@ -1,38 +1,93 @@
 use super::helper;
 use std::cmp::Ord;
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::collections::{BinaryHeap, HashSet};
 use std::hash::Hash;
 use tnj::air::instructions::builder::InstructionBuilder;
 use tnj::air::instructions::BasicBlock;
 use yaxpeax_arch::{Decoder, U8Reader};
-use yaxpeax_arm::armv8::a64::{DecodeError, InstDecoder, Opcode};
+use yaxpeax_arm::armv8::a64::{DecodeError, InstDecoder, Instruction, Opcode};
 
-use super::AArch64LifterError;
+use super::cfg::Transfer;
+use super::{
+    AArch64LifterError, CallMode, ControlFlowGraph, DataRegion, EdgeKind, JumpTable,
+    JumpTableMatcher,
+};
 
 /// Create basic blocks for the InstructionBuilder based off labels
 pub struct LabelResolver {
     checkpoints: UniqueHeap<Reverse<u64>>,
+    block_starts: BTreeSet<u64>,
+    transfers: BTreeMap<u64, Transfer>,
     blocks: HashMap<u64, BasicBlock>,
+    branch_targets: HashSet<u64>,
+    external_targets: BTreeSet<u64>,
+    external_blocks: BTreeMap<u64, BasicBlock>,
+    jump_tables: BTreeMap<u64, JumpTable>,
+    base_address: u64,
+    end_address: u64,
+    call_mode: CallMode,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 enum CheckpointType {
-    Conditional,
     Branch,
     DynamicJump,
+    Call,
+    Trap,
 }
 
 impl LabelResolver {
-    /// Create a new LabelResolver
+    /// Create a new LabelResolver for code loaded at address 0
     pub fn new(code: &[u8], decoder: &InstDecoder) -> Result<Self, AArch64LifterError> {
+        Self::with_base_address(code, 0, decoder)
+    }
+
+    /// Create a new LabelResolver for code loaded at `base_address`
+    pub fn with_base_address(
+        code: &[u8],
+        base_address: u64,
+        decoder: &InstDecoder,
+    ) -> Result<Self, AArch64LifterError> {
+        Self::with_call_mode(code, base_address, CallMode::default(), decoder)
+    }
+
+    /// Create a new LabelResolver for code loaded at `base_address`. In [`CallMode::Call`],
+    /// targets of `BL` get no block since the call continues at the next instruction.
+    pub fn with_call_mode(
+        code: &[u8],
+        base_address: u64,
+        call_mode: CallMode,
+        decoder: &InstDecoder,
+    ) -> Result<Self, AArch64LifterError> {
+        Self::with_data_regions(code, base_address, call_mode, &[], decoder)
+    }
+
+    /// Create a new LabelResolver for code loaded at `base_address` that also reads jump tables
+    /// from `data`. Tables inside the code itself are always found.
+    pub fn with_data_regions(
+        code: &[u8],
+        base_address: u64,
+        call_mode: CallMode,
+        data: &[DataRegion],
+        decoder: &InstDecoder,
+    ) -> Result<Self, AArch64LifterError> {
         let mut resolver = Self {
             checkpoints: UniqueHeap::new(),
+            block_starts: BTreeSet::new(),
+            transfers: BTreeMap::new(),
             blocks: HashMap::new(),
+            branch_targets: HashSet::new(),
+            external_targets: BTreeSet::new(),
+            external_blocks: BTreeMap::new(),
+            jump_tables: BTreeMap::new(),
+            base_address,
+            end_address: base_address.wrapping_add(code.len() as u64),
+            call_mode,
         };
 
-        resolver.get_checkpoints(code, decoder)?;
+        resolver.get_checkpoints(code, data, decoder)?;
 
         Ok(resolver)
     }
@@ -44,58 +99,122 @@ impl LabelResolver {
         builder: &mut InstructionBuilder,
         decoder: &InstDecoder,
     ) -> Result<(), AArch64LifterError> {
-        self.get_checkpoints(code, decoder)?;
+        self.get_checkpoints(code, &[], decoder)?;
         self.create_blocks(builder);
         Ok(())
     }
 
-    /// Get a block by address
+    /// Get a block by address, including exit blocks of branch targets outside the code
     pub fn get_block(&self, addr: u64) -> Option<BasicBlock> {
-        self.blocks.get(&addr).copied()
+        self.blocks
+            .get(&addr)
+            .or_else(|| self.external_blocks.get(&addr))
+            .copied()
+    }
+
+    /// Exit blocks for direct branch targets outside the code, sorted by target address
+    pub fn external_blocks(&self) -> impl Iterator<Item = (u64, BasicBlock)> + '_ {
+        self.external_blocks
+            .iter()
+            .map(|(&target, &block)| (target, block))
+    }
+
+    /// Whether `addr` lies inside the code
+    pub fn contains(&self, addr: u64) -> bool {
+        (self.base_address..self.end_address).contains(&addr)
+    }
+
+    /// Whether `addr` is the target of a direct branch inside the code
+    pub fn is_branch_target(&self, addr: u64) -> bool {
+        self.branch_targets.contains(&addr)
+    }
+
+    /// Jump table recovered for the `BR` at `addr`
+    pub fn jump_table(&self, addr: u64) -> Option<&JumpTable> {
+        self.jump_tables.get(&addr)
+    }
+
+    /// All recovered jump tables, sorted by the address of their `BR`
+    pub fn jump_tables(&self) -> impl Iterator<Item = &JumpTable> + '_ {
+        self.jump_tables.values()
+    }
+
+    /// Control-flow graph of the code, available without creating blocks
+    pub fn control_flow_graph(&self) -> ControlFlowGraph {
+        ControlFlowGraph::new(
+            self.base_address,
+            self.end_address,
+            &self.block_starts,
+            &self.transfers,
+        )
     }
 
     /// Store all addresses of branch-destinations or of instructions after branch-instructions
     fn get_checkpoints(
         &mut self,
         code: &[u8],
+        data: &[DataRegion],
         decoder: &InstDecoder,
     ) -> Result<(), AArch64LifterError> {
         const INSTRUCTION_SIZE: u64 = 4;
         let mut reader = U8Reader::new(code);
-        let mut address: u64 = 0;
+        let mut address = self.base_address;
+        let regions: Vec<DataRegion> = std::iter::once(DataRegion::new(self.base_address, code))
+            .chain(data.iter().copied())
+            .collect();
+        let mut matcher = JumpTableMatcher::new();
         loop {
             match decoder.decode(&mut reader) {
                 Ok(inst) => {
+                    if inst.opcode == Opcode::BR {
+                        if let Some(table) = matcher.match_branch(address, inst, &regions) {
+                            for &target in &table.targets {
+                                self.add_branch_target(target);
+                            }
+                            self.jump_tables.insert(address, table);
+                        }
+                    }
+                    matcher.push(address, inst);
                     let imm: Option<(i64, CheckpointType)> = match inst.opcode {
-                        Opcode::B | Opcode::BL | Opcode::Bcc(_) => Some((
-                            helper::get_pc_offset_as_int(inst.operands[0]),
-                            CheckpointType::Branch,
-                        )),
-                        Opcode::CBNZ | Opcode::CBZ => Some((
-                            helper::get_pc_offset_as_int(inst.operands[1]),
-                            CheckpointType::Branch,
-                        )),
-                        Opcode::TBNZ | Opcode::TBZ => Some((
-                            helper::get_pc_offset_as_int(inst.operands[2]),
-                            CheckpointType::Branch,
-                        )),
-                        Opcode::CCMP
-                        | Opcode::CCMN
-                        | Opcode::CSINC
-                        | Opcode::CSINV
-                        | Opcode::CSEL
-                        | Opcode::CSNEG
-                        | Opcode::SBFM
-                        | Opcode::UBFM
-                        | Opcode::BFM => Some((0, CheckpointType::Conditional)),
-                        Opcode::BLR | Opcode::BR => Some((0, CheckpointType::DynamicJump)),
+                        Opcode::BL if self.call_mode == CallMode::Call => {
+                            Some((0, CheckpointType::Call))
+                        }
+                        Opcode::B | Opcode::BL | Opcode::Bcc(_) => {
+                            Some((pc_offset(address, &inst, 0)?, CheckpointType::Branch))
+                        }
+                        Opcode::CBNZ | Opcode::CBZ => {
+                            Some((pc_offset(address, &inst, 1)?, CheckpointType::Branch))
+                        }
+                        Opcode::TBNZ | Opcode::TBZ => {
+                            Some((pc_offset(address, &inst, 2)?, CheckpointType::Branch))
+                        }
+                        Opcode::BLR
+                        | Opcode::BR
+                        | Opcode::BLRAA
+                        | Opcode::BLRAAZ
+                        | Opcode::BLRAB
+                        | Opcode::BLRABZ
+                        | Opcode::BRAA
+                        | Opcode::BRAAZ
+                        | Opcode::BRAB
+                        | Opcode::BRABZ => Some((0, CheckpointType::DynamicJump)),
+                        // traps end the block; the next instruction is only reached by a branch
+                        Opcode::BRK | Opcode::HLT | Opcode::UDF => Some((0, CheckpointType::Trap)),
                         _ => None,
                     };
+                    if helper::is_landing_pad(inst) {
+                        // indirect branches may enter the code at a landing pad
+                        self.add_checkpoint(address);
+                        self.branch_targets.insert(address);
+                    }
+                    if let Some(transfer) = self.transfer(address, &inst)? {
+                        self.transfers.insert(address, transfer);
+                    }
                     if let Some((imm, checkpoint_type)) = imm {
-                        self.checkpoints.push(Reverse(address + INSTRUCTION_SIZE));
+                        self.add_checkpoint(address + INSTRUCTION_SIZE);
                         if checkpoint_type == CheckpointType::Branch {
                             let jump_address = imm.wrapping_add(address as i64) as u64;
-                            self.checkpoints.push(Reverse(jump_address));
+                            self.add_branch_target(jump_address);
                         }
                     }
                 }
@@ -107,7 +226,67 @@ impl LabelResolver {
         Ok(())
     }
 
-    /// Create basic blocks based checkpoints
+    fn add_checkpoint(&mut self, addr: u64) {
+        self.checkpoints.push(Reverse(addr));
+        self.block_starts.insert(addr);
+    }
+
+    /// Edges of the instruction at `address` if it transfers control
+    fn transfer(
+        &self,
+        address: u64,
+        inst: &Instruction,
+    ) -> Result<Option<Transfer>, AArch64LifterError> {
+        let direct = |index| -> Result<Option<u64>, AArch64LifterError> {
+            let offset = pc_offset(address, inst, index)?;
+            Ok(Some(offset.wrapping_add(address as i64) as u64))
+        };
+        let call_returns = self.call_mode == CallMode::Call;
+        let (edges, falls_through) = match inst.opcode {
+            Opcode::B => (vec![(direct(0)?, EdgeKind::Branch)], false),
+            Opcode::Bcc(_) => (vec![(direct(0)?, EdgeKind::Conditional)], true),
+            Opcode::CBNZ | Opcode::CBZ => (vec![(direct(1)?, EdgeKind::Conditional)], true),
+            Opcode::TBNZ | Opcode::TBZ => (vec![(direct(2)?, EdgeKind::Conditional)], true),
+            Opcode::BL => (vec![(direct(0)?, EdgeKind::Call)], call_returns),
+            Opcode::BLR | Opcode::BLRAA | Opcode::BLRAAZ | Opcode::BLRAB | Opcode::BLRABZ => {
+                (vec![(None, EdgeKind::Call)], call_returns)
+            }
+            Opcode::BR | Opcode::BRAA | Opcode::BRAAZ | Opcode::BRAB | Opcode::BRABZ => {
+                match self.jump_tables.get(&address) {
+                    Some(table) => {
+                        let mut targets = table.targets.clone();
+                        targets.sort_unstable();
+                        targets.dedup();
+                        let edges = targets
+                            .into_iter()
+                            .map(|target| (Some(target), EdgeKind::Dynamic))
+                            .collect();
+                        (edges, false)
+                    }
+                    None => (vec![(None, EdgeKind::Dynamic)], false),
+                }
+            }
+            Opcode::RET | Opcode::RETAA | Opcode::RETAB => (vec![(None, EdgeKind::Return)], false),
+            Opcode::BRK | Opcode::HLT | Opcode::UDF => (Vec::new(), false),
+            _ => return Ok(None),
+        };
+        Ok(Some(Transfer {
+            edges,
+            falls_through,
+        }))
+    }
+
+    /// Direct branch targets get a block inside the code, or an exit block outside of it
+    fn add_branch_target(&mut self, target: u64) {
+        if self.contains(target) {
+            self.add_checkpoint(target);
+            self.branch_targets.insert(target);
+        } else {
+            self.external_targets.insert(target);
+        }
+    }
+
+    /// Create basic blocks based on checkpoints, and exit blocks for external branch targets
     pub fn create_blocks(&mut self, builder: &mut InstructionBuilder) {
         while !self.checkpoints.is_empty() {
             let checkpoint = match self.checkpoints.pop() {
@@ -118,9 +297,25 @@ impl LabelResolver {
             let b = builder.create_block(name.clone(), []);
             self.blocks.insert(checkpoint, b);
         }
+        for target in std::mem::take(&mut self.external_targets) {
+            let name = helper::get_external_block_name(target);
+            let b = builder.create_block(name, []);
+            self.external_blocks.insert(target, b);
+        }
     }
 }
 
+/// Branch offset of operand `index`, failing for malformed branches instead of panicking
+fn pc_offset(address: u64, inst: &Instruction, index: usize) -> Result<i64, AArch64LifterError> {
+    helper::get_pc_offset_as_int(inst.operands[index]).ok_or_else(|| {
+        AArch64LifterError::InvalidInstruction {
+            pc: address,
+            text: inst.to_string(),
+            reason: format!("expected pc offset, got {:?}", inst.operands[index]),
+        }
+    })
+}
+
 struct UniqueHeap<T>
 where
     T: Ord + Hash + Clone,
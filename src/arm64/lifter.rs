@@ -1,31 +1,107 @@
-use crate::arm64::LabelResolver;
+use crate::arm64::{
+    dot, helper, DataRegion, LabelResolver, LiftReport, LiftedCall, MemoryOrderings, OrderingKind,
+    OrderingMarker, ReportEntry, ReportKind, Syscall, SyscallTable, TrapSite,
+};
 use crate::Lifter;
 use std::io::Cursor;
 use target_lexicon::{Aarch64Architecture, Architecture};
 use thiserror::Error;
 use tnj::air::instructions::builder::InstructionBuilder;
-use tnj::air::instructions::CodeRegion;
+use tnj::air::instructions::{BasicBlock, CodeRegion};
 use tnj::arch::get_arch;
 use tnj::pcc;
 use tnj::pcc::Proof;
 use tnj::sym::{Expr, TypedExprPool};
+use tnj::types::Type;
 use yaxpeax_arch::{Arch, Decoder, U8Reader};
-use yaxpeax_arm::armv8::a64::{ARMv8, DecodeError, InstDecoder};
+use yaxpeax_arm::armv8::a64::{ARMv8, DecodeError, InstDecoder, Instruction, Opcode, Operand};
 
+mod atomics;
+mod bitfield;
+mod calls;
 mod conditions;
+mod exclusive;
 mod flags;
+mod fp;
 mod insts;
+mod jump_table;
+mod neon;
 mod operands;
+mod pac;
 mod regs;
+mod simd_mem;
+mod svc;
+mod sysregs;
+mod traps;
 
 /// A lifter for AArch64
 pub struct AArch64Lifter<'a> {
     code: &'a [u8],
     proofs: &'a [u8],
+    base_address: u64,
+    mode: LiftMode,
+    call_mode: CallMode,
+    clobber_model: ClobberModel,
+    syscall_mode: SyscallMode,
+    syscall_table: Option<&'a dyn SyscallTable>,
+    data_regions: Vec<DataRegion<'a>>,
+}
+
+/// How the lifter handles instructions whose semantics are not modelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LiftMode {
+    /// Write opaque values to the destination and record a warning
+    #[default]
+    Lenient,
+    /// Fail with [`AArch64LifterError::UnsupportedInstruction`]
+    Strict,
+}
+
+/// How the lifter represents `BL` and `BLR`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallMode {
+    /// `BL` jumps to the callee block and `BLR` is a dynamic jump, so callee bodies inside the
+    /// lifted code become part of the caller's control flow
+    #[default]
+    Jump,
+    /// Calls set the link register and continue at the return address `pc + 4`, without an edge
+    /// into the callee
+    Call,
+}
+
+/// Registers assumed to be modified by a callee in [`CallMode::Call`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClobberModel {
+    /// The callee preserves all registers
+    #[default]
+    None,
+    /// AAPCS64: the callee may modify the caller-saved registers x0–x18 and the flags
+    Aapcs64,
+}
+
+/// How the lifter represents `SVC`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyscallMode {
+    /// The system call is ignored and the next instruction becomes an entry
+    #[default]
+    Ignore,
+    /// The system call reads `x8` and `x0`–`x5`, clobbers `x0` with an opaque result and returns
+    /// to the next instruction
+    Model,
+}
+
+/// Options of a lift that the lifter state acts on
+#[derive(Debug, Clone, Copy)]
+struct LiftOptions {
+    mode: LiftMode,
+    call_mode: CallMode,
+    clobber_model: ClobberModel,
+    syscall_mode: SyscallMode,
 }
 
 const INSTRUCTION_SIZE: u64 = 4;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Flag {
     N,
     Z,
@@ -33,7 +109,126 @@ enum Flag {
     V,
 }
 
-impl AArch64Lifter<'_> {
+impl<'a> AArch64Lifter<'a> {
+    /// Set the virtual address the code is loaded at.
+    ///
+    /// Block names, PC-relative values and proof constraint lookups use addresses starting at this
+    /// base, which defaults to 0.
+    pub fn with_base_address(mut self, base_address: u64) -> Self {
+        self.base_address = base_address;
+        self
+    }
+
+    /// Set how instructions without modelled semantics are handled
+    pub fn with_mode(mut self, mode: LiftMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set how calls are represented
+    pub fn with_call_mode(mut self, call_mode: CallMode) -> Self {
+        self.call_mode = call_mode;
+        self
+    }
+
+    /// Set which registers are clobbered after a call in [`CallMode::Call`]
+    pub fn with_clobber_model(mut self, clobber_model: ClobberModel) -> Self {
+        self.clobber_model = clobber_model;
+        self
+    }
+
+    /// Set how system calls are represented
+    pub fn with_syscall_mode(mut self, syscall_mode: SyscallMode) -> Self {
+        self.syscall_mode = syscall_mode;
+        self
+    }
+
+    /// Set the table naming system calls in [`AArch64Lifter::lift_with_syscalls`]
+    pub fn with_syscall_table(mut self, syscall_table: &'a dyn SyscallTable) -> Self {
+        self.syscall_table = Some(syscall_table);
+        self
+    }
+
+    /// Add read-only memory, such as `.rodata`, that jump tables of `BR` may be read from
+    pub fn with_data_region(mut self, region: DataRegion<'a>) -> Self {
+        self.data_regions.push(region);
+        self
+    }
+
+    /// Lift to a CodeRegion, returning an [`AArch64LifterError::UnsupportedInstruction`] warning
+    /// for every instruction lifted to an opaque value in lenient mode.
+    pub fn lift_with_warnings(
+        &self,
+    ) -> Result<(CodeRegion, Vec<AArch64LifterError>), AArch64LifterError> {
+        let (code_region, output) = self.lift_region()?;
+        Ok((code_region, output.warnings))
+    }
+
+    /// Lift to a CodeRegion together with a report of all instructions that were lifted to opaque
+    /// values, skipped or ignored.
+    pub fn lift_with_report(&self) -> Result<(CodeRegion, LiftReport), AArch64LifterError> {
+        let (code_region, output) = self.lift_region()?;
+        Ok((code_region, output.report))
+    }
+
+    /// Lift to a CodeRegion together with the memory ordering of every atomic or ordered access.
+    pub fn lift_with_orderings(&self) -> Result<(CodeRegion, MemoryOrderings), AArch64LifterError> {
+        let (code_region, output) = self.lift_region()?;
+        Ok((code_region, output.orderings))
+    }
+
+    /// Lift to a CodeRegion together with every system call in [`SyscallMode::Model`], named by
+    /// the syscall table if one is set.
+    pub fn lift_with_syscalls(&self) -> Result<(CodeRegion, Vec<Syscall>), AArch64LifterError> {
+        let (code_region, mut output) = self.lift_region()?;
+        if let Some(table) = self.syscall_table {
+            for syscall in &mut output.syscalls {
+                syscall.name = syscall
+                    .number
+                    .and_then(|number| table.name(number))
+                    .map(str::to_string);
+            }
+        }
+        Ok((code_region, output.syscalls))
+    }
+
+    /// Lift to a CodeRegion together with every call and its callee in [`CallMode::Call`].
+    pub fn lift_with_calls(&self) -> Result<(CodeRegion, Vec<LiftedCall>), AArch64LifterError> {
+        let (code_region, output) = self.lift_region()?;
+        Ok((code_region, output.calls))
+    }
+
+    /// Lift to a CodeRegion together with every unconditional trap and its trap code.
+    pub fn lift_with_traps(&self) -> Result<(CodeRegion, Vec<TrapSite>), AArch64LifterError> {
+        let (code_region, output) = self.lift_region()?;
+        Ok((code_region, output.traps))
+    }
+
+    fn lift_region(&self) -> Result<(CodeRegion, LiftOutput), AArch64LifterError> {
+        let arch = get_arch(Architecture::Aarch64(Aarch64Architecture::Aarch64)).unwrap();
+
+        let (proof, exprs) = self.parse_proofs()?.unwrap_or_default();
+        let mut code_region = CodeRegion::with_exprs(arch, exprs);
+
+        let state = LifterState::new(
+            &mut code_region,
+            self.code,
+            self.base_address,
+            LiftOptions {
+                mode: self.mode,
+                call_mode: self.call_mode,
+                clobber_model: self.clobber_model,
+                syscall_mode: self.syscall_mode,
+            },
+            &self.data_regions,
+            proof,
+        )?;
+
+        let output = state.lift()?;
+
+        Ok((code_region, output))
+    }
+
     /// Disassemble code and print to a string.
     pub fn disassemble<W>(&self, w: &mut W) -> Result<(), AArch64DisassemblerError>
     where
@@ -43,7 +238,7 @@ impl AArch64Lifter<'_> {
         let mut reader = U8Reader::new(self.code);
         let (proof, exprs) = self.parse_proofs()?.unwrap_or_default();
 
-        let mut pc = 0u64;
+        let mut pc = self.base_address;
 
         loop {
             match decoder.decode(&mut reader) {
@@ -64,6 +259,18 @@ impl AArch64Lifter<'_> {
         Ok(())
     }
 
+    /// Write the control flow of the lifted code region as a Graphviz DOT graph, with a node for
+    /// each block listing its AIR, including the cases of jump tables and the blocks leaving the
+    /// code, and an edge for each target of its terminators.
+    pub fn write_dot<W>(&self, w: &mut W) -> Result<(), AArch64DisassemblerError>
+    where
+        W: ?Sized + std::io::Write,
+    {
+        let code_region = self.lift()?;
+        dot::write_code_region(w, &code_region)?;
+        Ok(())
+    }
+
     fn print_assertions<W>(
         w: &mut W,
         exprs: &TypedExprPool,
@@ -99,42 +306,82 @@ impl<'a> Lifter<'a> for AArch64Lifter<'a> {
     type E = AArch64LifterError;
 
     fn new(code: &'a [u8], proofs: &'a [u8]) -> Self {
-        Self { code, proofs }
+        Self {
+            code,
+            proofs,
+            base_address: 0,
+            mode: LiftMode::default(),
+            call_mode: CallMode::default(),
+            clobber_model: ClobberModel::default(),
+            syscall_mode: SyscallMode::default(),
+            syscall_table: None,
+            data_regions: Vec::new(),
+        }
     }
 
     fn lift(&self) -> Result<CodeRegion, Self::E> {
-        let arch = get_arch(Architecture::Aarch64(Aarch64Architecture::Aarch64)).unwrap();
-
-        let (proof, exprs) = self.parse_proofs()?.unwrap_or_default();
-        let mut code_region = CodeRegion::with_exprs(arch, exprs);
-
-        let state = LifterState::new(&mut code_region, self.code, proof)?;
-
-        state.lift()?;
-
+        let (code_region, _warnings) = self.lift_with_warnings()?;
         Ok(code_region)
     }
 }
 
-/// Private lifter tate
+/// Side results of lifting a code region
+struct LiftOutput {
+    warnings: Vec<AArch64LifterError>,
+    report: LiftReport,
+    orderings: MemoryOrderings,
+    syscalls: Vec<Syscall>,
+    traps: Vec<TrapSite>,
+    calls: Vec<LiftedCall>,
+}
+
+/// Private lifter state
 struct LifterState<'a> {
     builder: InstructionBuilder<'a>,
     label_resolver: LabelResolver,
     decoder: InstDecoder,
     reader: U8Reader<'a>,
     proof: Proof,
+    base_address: u64,
+    /// Address of the instruction currently being lifted
+    pc: u64,
+    /// Instruction currently being lifted
+    inst: Instruction,
+    mode: LiftMode,
+    call_mode: CallMode,
+    clobber_model: ClobberModel,
+    syscall_mode: SyscallMode,
+    warnings: Vec<AArch64LifterError>,
+    report: LiftReport,
+    orderings: MemoryOrderings,
+    /// Reservation of the last load-exclusive in the current block, if any
+    monitor: Option<exclusive::ExclusiveMonitor>,
+    /// Value of `x8` if it was set to a constant earlier in the current block
+    syscall_number: Option<u64>,
+    syscalls: Vec<Syscall>,
+    traps: Vec<TrapSite>,
+    calls: Vec<LiftedCall>,
 }
 
 impl<'a> LifterState<'a> {
     fn new(
         code_region: &'a mut CodeRegion,
         code: &'a [u8],
+        base_address: u64,
+        options: LiftOptions,
+        data_regions: &[DataRegion],
         proof: Proof,
     ) -> Result<Self, AArch64LifterError> {
         let builder = code_region.insert();
         let decoder = <ARMv8 as Arch>::Decoder::default();
         let reader = U8Reader::new(code);
-        let label_resolver = LabelResolver::new(code, &decoder)?;
+        let label_resolver = LabelResolver::with_data_regions(
+            code,
+            base_address,
+            options.call_mode,
+            data_regions,
+            &decoder,
+        )?;
 
         Ok(Self {
             builder,
@@ -142,24 +389,45 @@ impl<'a> LifterState<'a> {
             decoder,
             reader,
             proof,
+            base_address,
+            pc: base_address,
+            inst: Instruction::default(),
+            mode: options.mode,
+            call_mode: options.call_mode,
+            clobber_model: options.clobber_model,
+            syscall_mode: options.syscall_mode,
+            warnings: Vec::new(),
+            report: LiftReport::default(),
+            orderings: MemoryOrderings::default(),
+            monitor: None,
+            syscall_number: None,
+            syscalls: Vec::new(),
+            traps: Vec::new(),
+            calls: Vec::new(),
         })
     }
 
-    fn lift(mut self) -> Result<(), AArch64LifterError> {
+    fn lift(mut self) -> Result<LiftOutput, AArch64LifterError> {
         self.label_resolver.create_blocks(&mut self.builder);
 
-        let mut pc = 0u64;
+        let mut pc = self.base_address;
 
         loop {
             match self.decoder.decode(&mut self.reader) {
                 Ok(inst) => {
+                    self.pc = pc;
                     let block = self.label_resolver.get_block(pc);
                     if let Some(block) = block {
                         self.builder.jump(block, vec![]);
                         self.builder.set_insert_block(block);
+                        // the values of the previous block do not reach this one
+                        self.monitor = None;
+                        self.syscall_number = None;
                     }
+                    self.inst = inst;
 
                     self.lift_inst(pc, inst)?;
+                    self.track_syscall_number(inst);
                 }
                 Err(DecodeError::ExhaustedInput) => break,
                 Err(e) => return Err(AArch64LifterError::DecodeError(e)),
@@ -168,9 +436,133 @@ impl<'a> LifterState<'a> {
             pc += INSTRUCTION_SIZE;
         }
 
+        self.lift_external_blocks();
+
+        Ok(LiftOutput {
+            warnings: self.warnings,
+            report: self.report,
+            orderings: self.orderings,
+            syscalls: self.syscalls,
+            traps: self.traps,
+            calls: self.calls,
+        })
+    }
+
+    /// Leave the lifted code through an explicit edge for each direct branch target outside of it
+    fn lift_external_blocks(&mut self) {
+        let external_blocks: Vec<_> = self.label_resolver.external_blocks().collect();
+        for (target, block) in external_blocks {
+            self.builder.set_insert_block(block);
+            let target = self.builder.iconst(target);
+            self.builder.dynamic_jump(target);
+        }
+    }
+
+    /// Fails in strict mode, otherwise records a warning for an instruction without semantics
+    fn unsupported(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+        kind: ReportKind,
+    ) -> Result<(), AArch64LifterError> {
+        let error = AArch64LifterError::UnsupportedInstruction {
+            pc,
+            opcode: inst.opcode,
+            text: inst.to_string(),
+        };
+        match self.mode {
+            LiftMode::Strict => Err(error),
+            LiftMode::Lenient => {
+                self.warnings.push(error);
+                self.report_inst(pc, inst, kind);
+                Ok(())
+            }
+        }
+    }
+
+    /// Lifts an instruction without modelled semantics by writing an opaque value to its general
+    /// purpose destination, or to its scalar view of a vector register, as for half precision
+    /// arithmetic which has no float type
+    fn lift_opaque(&mut self, pc: u64, inst: Instruction) -> Result<(), AArch64LifterError> {
+        self.unsupported(pc, inst, ReportKind::Opaque)?;
+        if let Operand::SIMDRegister(sz, d) = inst.operands[0] {
+            // the scalar view is overwritten, so its previous value must not survive
+            if let Some(ty) = fp::bits_type(sz) {
+                let val = self.builder.opaque(ty);
+                self.write_fp_bits(d, val.into(), ty)?;
+            }
+            return Ok(());
+        }
+        let is_general_purpose = helper::is_operand_general_purpose(inst.operands[0]);
+        // writes to the zero register and to register pairs are dropped
+        if let (true, Ok(dst_reg)) = (is_general_purpose, self.get_dst_reg(inst)) {
+            if dst_reg.0 != 31 {
+                let op_type = self.get_type_by_inst(inst)?;
+                let val = self.builder.opaque(op_type);
+                self.write_reg(val, dst_reg, op_type)?;
+            }
+        }
         Ok(())
     }
 
+    /// Records a system instruction whose effect is not modelled, or fails in strict mode
+    fn ignored(&mut self, pc: u64, inst: Instruction) -> Result<(), AArch64LifterError> {
+        self.unsupported(pc, inst, ReportKind::IgnoredSystem)
+    }
+
+    fn report_inst(&mut self, pc: u64, inst: Instruction, kind: ReportKind) {
+        self.report.push(ReportEntry {
+            pc,
+            kind,
+            opcode: inst.opcode,
+            text: inst.to_string(),
+        });
+    }
+
+    /// Records the memory ordering of the instruction at `pc` on the current block
+    fn record_ordering(&mut self, pc: u64, kind: OrderingKind) {
+        self.orderings.push(OrderingMarker {
+            pc,
+            block: self.builder.current_block(),
+            kind,
+        });
+    }
+
+    /// Error for the instruction currently being lifted
+    fn invalid_instruction(&self, reason: impl Into<String>) -> AArch64LifterError {
+        AArch64LifterError::InvalidInstruction {
+            pc: self.pc,
+            text: self.inst.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn get_type_by_inst(&self, inst: Instruction) -> Result<Type, AArch64LifterError> {
+        helper::get_type_by_inst(inst).ok_or_else(|| {
+            self.invalid_instruction(format!(
+                "invalid destination operand {:?}",
+                inst.operands[0]
+            ))
+        })
+    }
+
+    fn get_pc_offset(&self, operand: Operand) -> Result<i64, AArch64LifterError> {
+        helper::get_pc_offset_as_int(operand).ok_or_else(|| {
+            self.invalid_instruction(format!("expected pc offset, got {:?}", operand))
+        })
+    }
+
+    /// Returns the block starting at `target`, which the label resolver must have created
+    fn get_block(&self, target: u64) -> Result<BasicBlock, AArch64LifterError> {
+        self.label_resolver
+            .get_block(target)
+            .ok_or_else(|| AArch64LifterError::MissingBlock {
+                pc: self.pc,
+                text: self.inst.to_string(),
+                target,
+            })
+    }
+
     fn mark_next_block_as_entry(&mut self, pc: u64) {
         let next_pc = pc + INSTRUCTION_SIZE;
         if let Some(block) = self.label_resolver.get_block(next_pc) {
@@ -196,6 +588,39 @@ pub enum AArch64LifterError {
     #[error("{0}")]
     CustomError(String),
 
+    /// Instruction whose semantics are not modelled by the lifter
+    #[error("Unsupported instruction at {pc:#x}: {text}")]
+    UnsupportedInstruction {
+        /// Address of the instruction
+        pc: u64,
+        /// Decoded opcode
+        opcode: Opcode,
+        /// Disassembled instruction
+        text: String,
+    },
+
+    /// Instruction with operands the lifter cannot handle
+    #[error("Invalid instruction at {pc:#x}: {text}: {reason}")]
+    InvalidInstruction {
+        /// Address of the instruction
+        pc: u64,
+        /// Disassembled instruction
+        text: String,
+        /// What is wrong with the instruction
+        reason: String,
+    },
+
+    /// Branch to an address for which the label resolver created no block
+    #[error("Missing block for {target:#x} at {pc:#x}: {text}")]
+    MissingBlock {
+        /// Address of the instruction
+        pc: u64,
+        /// Disassembled instruction
+        text: String,
+        /// Address of the missing block
+        target: u64,
+    },
+
     /// Proof decode error
     #[error("Error decoding pcc proofs: {0}")]
     Pcc(#[from] pcc::read::Error),
@@ -215,4 +640,8 @@ pub enum AArch64DisassemblerError {
     /// Proof decode error
     #[error("Error decoding pcc proofs: {0}")]
     Pcc(#[from] pcc::read::Error),
+
+    /// Error lifting the code
+    #[error("{0}")]
+    ControlFlow(#[from] AArch64LifterError),
 }
@@ -0,0 +1,247 @@
+use crate::arm64::lifter::{LifterState, SpOrZrReg};
+use crate::arm64::{helper, AArch64LifterError, MemoryOrdering, OrderingKind};
+use tnj::air::instructions::Value;
+use tnj::arch::reg::Reg;
+use tnj::types::cmp::CmpTy;
+use tnj::types::{Type, BOOL, I16, I64, I8};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, SizeCode};
+
+/// Read-modify-write operation of an LSE atomic
+#[derive(Debug, Clone, Copy)]
+enum AtomicOp {
+    Add,
+    Clr,
+    Eor,
+    Set,
+    Smax,
+    Smin,
+    Umax,
+    Umin,
+    Swp,
+}
+
+/// Access width of an LSE atomic, narrower than its registers for the `B` and `H` forms
+#[derive(Debug, Clone, Copy)]
+enum Width {
+    Byte,
+    Half,
+    Reg,
+}
+
+/// Operation, width and acquire/release bits of an `LD<op>` or `SWP` instruction
+fn rmw(opcode: Opcode) -> Option<(AtomicOp, Width, u8)> {
+    let op = match opcode {
+        Opcode::LDADD(ar) => (AtomicOp::Add, Width::Reg, ar),
+        Opcode::LDADDB(ar) => (AtomicOp::Add, Width::Byte, ar),
+        Opcode::LDADDH(ar) => (AtomicOp::Add, Width::Half, ar),
+        Opcode::LDCLR(ar) => (AtomicOp::Clr, Width::Reg, ar),
+        Opcode::LDCLRB(ar) => (AtomicOp::Clr, Width::Byte, ar),
+        Opcode::LDCLRH(ar) => (AtomicOp::Clr, Width::Half, ar),
+        Opcode::LDEOR(ar) => (AtomicOp::Eor, Width::Reg, ar),
+        Opcode::LDEORB(ar) => (AtomicOp::Eor, Width::Byte, ar),
+        Opcode::LDEORH(ar) => (AtomicOp::Eor, Width::Half, ar),
+        Opcode::LDSET(ar) => (AtomicOp::Set, Width::Reg, ar),
+        Opcode::LDSETB(ar) => (AtomicOp::Set, Width::Byte, ar),
+        Opcode::LDSETH(ar) => (AtomicOp::Set, Width::Half, ar),
+        Opcode::LDSMAX(ar) => (AtomicOp::Smax, Width::Reg, ar),
+        Opcode::LDSMAXB(ar) => (AtomicOp::Smax, Width::Byte, ar),
+        Opcode::LDSMAXH(ar) => (AtomicOp::Smax, Width::Half, ar),
+        Opcode::LDSMIN(ar) => (AtomicOp::Smin, Width::Reg, ar),
+        Opcode::LDSMINB(ar) => (AtomicOp::Smin, Width::Byte, ar),
+        Opcode::LDSMINH(ar) => (AtomicOp::Smin, Width::Half, ar),
+        Opcode::LDUMAX(ar) => (AtomicOp::Umax, Width::Reg, ar),
+        Opcode::LDUMAXB(ar) => (AtomicOp::Umax, Width::Byte, ar),
+        Opcode::LDUMAXH(ar) => (AtomicOp::Umax, Width::Half, ar),
+        Opcode::LDUMIN(ar) => (AtomicOp::Umin, Width::Reg, ar),
+        Opcode::LDUMINB(ar) => (AtomicOp::Umin, Width::Byte, ar),
+        Opcode::LDUMINH(ar) => (AtomicOp::Umin, Width::Half, ar),
+        Opcode::SWP(ar) => (AtomicOp::Swp, Width::Reg, ar),
+        Opcode::SWPB(ar) => (AtomicOp::Swp, Width::Byte, ar),
+        Opcode::SWPH(ar) => (AtomicOp::Swp, Width::Half, ar),
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Width and acquire/release bits of a `CAS` instruction
+fn cas(opcode: Opcode) -> Option<(Width, u8)> {
+    match opcode {
+        Opcode::CAS(ar) => Some((Width::Reg, ar)),
+        Opcode::CASB(ar) => Some((Width::Byte, ar)),
+        Opcode::CASH(ar) => Some((Width::Half, ar)),
+        _ => None,
+    }
+}
+
+fn access_type(width: Width, reg_ty: Type) -> Type {
+    match width {
+        Width::Byte => I8,
+        Width::Half => I16,
+        Width::Reg => reg_ty,
+    }
+}
+
+impl LifterState<'_> {
+    /// Whether `opcode` is an ARMv8.1 LSE atomic
+    pub(crate) fn is_lse_atomic(opcode: Opcode) -> bool {
+        rmw(opcode).is_some() || cas(opcode).is_some() || matches!(opcode, Opcode::CASP(_))
+    }
+
+    /// Lifts an LSE atomic to a load-modify-store sequence that returns the old value
+    pub(crate) fn lift_lse_atomic(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let ar = match (rmw(inst.opcode), cas(inst.opcode), inst.opcode) {
+            (Some((_, _, ar)), ..) | (_, Some((_, ar)), _) | (.., Opcode::CASP(ar)) => ar,
+            _ => return Err(self.invalid_instruction("not an LSE atomic")),
+        };
+        // a release orders the accesses before the store, so its marker precedes the AIR
+        let ordering = MemoryOrdering::from_ar_bits(ar);
+        if ordering.releases() {
+            self.record_ordering(pc, OrderingKind::Access(ordering));
+        }
+
+        if let Some((op, width, _)) = rmw(inst.opcode) {
+            self.lift_rmw(inst, op, width)?;
+        } else if let Some((width, _)) = cas(inst.opcode) {
+            self.lift_cas(inst, width)?;
+        } else {
+            self.lift_casp(inst)?;
+        }
+
+        if !ordering.releases() {
+            self.record_ordering(pc, OrderingKind::Access(ordering));
+        }
+        Ok(())
+    }
+
+    /// `LD<op> Rs, Rt, [Xn]` and `SWP Rs, Rt, [Xn]`
+    fn lift_rmw(
+        &mut self,
+        inst: Instruction,
+        op: AtomicOp,
+        width: Width,
+    ) -> Result<(), AArch64LifterError> {
+        let [Operand::Register(sz, s), Operand::Register(_, t), mem, ..] = inst.operands else {
+            return Err(self.invalid_instruction("expected Rs, Rt, [Xn]"));
+        };
+        let reg_ty = helper::get_type_by_sizecode(sz);
+        let ty = access_type(width, reg_ty);
+
+        let src = self.atomic_reg(s, ty);
+        let (address, _) = self.get_address(mem)?;
+        let old: Value = self.builder.load(address, ty).into();
+        let new = match op {
+            AtomicOp::Add => self.builder.wrapping_add(old, src, ty).into(),
+            AtomicOp::Clr => {
+                let not_src = self.builder.bitwise_not(src, ty);
+                self.builder.and(old, not_src, ty).into()
+            }
+            AtomicOp::Eor => self.builder.xor(old, src, ty).into(),
+            AtomicOp::Set => self.builder.or(old, src, ty).into(),
+            AtomicOp::Smax => {
+                let gt = self.builder.scmp(CmpTy::Gt, old, src, ty);
+                self.select(gt, old, src, ty)
+            }
+            AtomicOp::Smin => {
+                let lt = self.builder.scmp(CmpTy::Lt, old, src, ty);
+                self.select(lt, old, src, ty)
+            }
+            AtomicOp::Umax => {
+                let gt = self.builder.ucmp(CmpTy::Gt, old, src, ty);
+                self.select(gt, old, src, ty)
+            }
+            AtomicOp::Umin => {
+                let lt = self.builder.ucmp(CmpTy::Lt, old, src, ty);
+                self.select(lt, old, src, ty)
+            }
+            AtomicOp::Swp => src,
+        };
+        self.builder.store(new, address, ty);
+        self.write_atomic_result(t, old, ty, reg_ty)
+    }
+
+    /// `CAS Rs, Rt, [Xn]` stores `Rt` if memory equals `Rs`, and always returns the old value
+    /// in `Rs`
+    fn lift_cas(&mut self, inst: Instruction, width: Width) -> Result<(), AArch64LifterError> {
+        let [Operand::Register(sz, s), Operand::Register(_, t), mem, ..] = inst.operands else {
+            return Err(self.invalid_instruction("expected Rs, Rt, [Xn]"));
+        };
+        let reg_ty = helper::get_type_by_sizecode(sz);
+        let ty = access_type(width, reg_ty);
+
+        let expected = self.atomic_reg(s, ty);
+        let new = self.atomic_reg(t, ty);
+        let (address, _) = self.get_address(mem)?;
+        let old: Value = self.builder.load(address, ty).into();
+        let eq = self.builder.icmp(CmpTy::Eq, old, expected, ty);
+        // storing the old value back on mismatch leaves memory unchanged
+        let val = self.select(eq, new, old, ty);
+        self.builder.store(val, address, ty);
+        self.write_atomic_result(s, old, ty, reg_ty)
+    }
+
+    /// `CASP Rs, Rs+1, Rt, Rt+1, [Xn]` compares and swaps a pair of consecutive words
+    fn lift_casp(&mut self, inst: Instruction) -> Result<(), AArch64LifterError> {
+        let [Operand::RegisterPair(sz, s), Operand::RegisterPair(_, t), mem, ..] = inst.operands
+        else {
+            return Err(self.invalid_instruction("expected register pairs and [Xn]"));
+        };
+        let ty = helper::get_type_by_sizecode(sz);
+        let size = match sz {
+            SizeCode::X => 8,
+            SizeCode::W => 4,
+        };
+
+        let expected_lo = self.atomic_reg(s, ty);
+        let expected_hi = self.atomic_reg(s + 1, ty);
+        let new_lo = self.atomic_reg(t, ty);
+        let new_hi = self.atomic_reg(t + 1, ty);
+        let (address, _) = self.get_address(mem)?;
+        let size = self.builder.iconst(size);
+        let address_hi: Value = self.builder.wrapping_add(address, size, I64).into();
+        let old_lo: Value = self.builder.load(address, ty).into();
+        let old_hi: Value = self.builder.load(address_hi, ty).into();
+        let eq_lo = self.builder.icmp(CmpTy::Eq, old_lo, expected_lo, ty);
+        let eq_hi = self.builder.icmp(CmpTy::Eq, old_hi, expected_hi, ty);
+        let eq = self.builder.and(eq_lo, eq_hi, BOOL);
+        let val_lo = self.select(eq, new_lo, old_lo, ty);
+        let val_hi = self.select(eq, new_hi, old_hi, ty);
+        self.builder.store(val_lo, address, ty);
+        self.builder.store(val_hi, address_hi, ty);
+        self.write_atomic_result(s, old_lo, ty, ty)?;
+        self.write_atomic_result(s + 1, old_hi, ty, ty)
+    }
+
+    /// Low `ty` bits of register `reg`, where 31 is the zero register
+    fn atomic_reg(&mut self, reg: u16, ty: Type) -> Value {
+        let val = self.reg_val(reg, SpOrZrReg::Zr);
+        if ty == I64 {
+            val
+        } else {
+            self.builder.trunc(val, I64, ty).into()
+        }
+    }
+
+    /// Writes the old memory value zero extended to the register width; the `ST<op>` aliases
+    /// discard it into the zero register
+    fn write_atomic_result(
+        &mut self,
+        reg: u16,
+        val: Value,
+        ty: Type,
+        reg_ty: Type,
+    ) -> Result<(), AArch64LifterError> {
+        if reg == 31 {
+            return Ok(());
+        }
+        let val = if ty == reg_ty {
+            val
+        } else {
+            self.builder.zext(val, ty, reg_ty).into()
+        };
+        self.write_reg(val, Reg(reg as u32), reg_ty)
+    }
+}
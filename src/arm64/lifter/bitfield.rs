@@ -0,0 +1,114 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::AArch64LifterError;
+use tnj::air::instructions::Value;
+use tnj::types::{Type, I64};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand};
+
+/// Placement of the bitfield of a `BFM`, `SBFM` or `UBFM`, fixed by its `immr` and `imms`
+/// immediates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bitfield {
+    /// Left shift that moves the top bit of the field to the top of the register
+    left: u64,
+    /// Right shift that then moves the field to its destination bits
+    right: u64,
+    /// Lowest destination bit of the field
+    position: u64,
+    /// Number of bits in the field
+    width: u64,
+}
+
+impl Bitfield {
+    fn new(immr: u64, imms: u64, reg_size: u64) -> Self {
+        let left = reg_size - 1 - imms;
+        if imms >= immr {
+            // bits imms:immr of the source to the low bits of the destination (LSR, ASR, SXTB,
+            // UBFX, BFXIL, ...)
+            let width = imms - immr + 1;
+            Self {
+                left,
+                right: reg_size - width,
+                position: 0,
+                width,
+            }
+        } else {
+            // the low imms+1 bits of the source to bit regsize-immr of the destination (LSL,
+            // SBFIZ, BFI, ...)
+            Self {
+                left,
+                right: immr - imms - 1,
+                position: reg_size - immr,
+                width: imms + 1,
+            }
+        }
+    }
+
+    /// Destination bits written by the field
+    fn mask(&self) -> u64 {
+        (u64::MAX >> (64 - self.width)) << self.position
+    }
+}
+
+impl LifterState<'_> {
+    /// Lifts `BFM`, `SBFM` and `UBFM` to shifts and masks by constants, since the placement of
+    /// the bitfield is part of the encoding
+    pub(crate) fn lift_bitfield_move(
+        &mut self,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let dst_reg = self.get_dst_reg(inst)?;
+        let op_type = self.get_type_by_inst(inst)?;
+        let reg_size = if op_type == I64 { 64 } else { 32 };
+        let immr = self.bitfield_immediate(inst.operands[2])?;
+        let imms = self.bitfield_immediate(inst.operands[3])?;
+        if immr >= reg_size || imms >= reg_size {
+            return Err(self.invalid_instruction("bitfield immediate exceeds the register size"));
+        }
+        let field = Bitfield::new(immr, imms, reg_size);
+
+        let src = self.get_value(inst.operands[1])?;
+        let val = self.shift_left(src, field.left, op_type);
+        let val = match inst.opcode {
+            Opcode::SBFM => self.shift_right(val, field.right, op_type, true),
+            Opcode::UBFM => self.shift_right(val, field.right, op_type, false),
+            Opcode::BFM => {
+                // insert the field into the destination, keeping its other bits
+                let val = self.shift_right(val, field.right, op_type, false);
+                let dst = self.get_value(inst.operands[0])?;
+                let reg_mask = u64::MAX >> (64 - reg_size);
+                let keep_mask = self.builder.iconst(!field.mask() & reg_mask);
+                let dst = self.builder.and(dst, keep_mask, op_type);
+                self.builder.or(dst, val, op_type).into()
+            }
+            _ => return Err(self.invalid_instruction("not a bitfield move")),
+        };
+        self.write_reg(val, dst_reg, op_type)
+    }
+
+    fn shift_left(&mut self, val: Value, amount: u64, ty: Type) -> Value {
+        if amount == 0 {
+            return val;
+        }
+        let amount = self.builder.iconst(amount);
+        self.builder.lshl(val, amount, ty).into()
+    }
+
+    fn shift_right(&mut self, val: Value, amount: u64, ty: Type, signed: bool) -> Value {
+        if amount == 0 {
+            return val;
+        }
+        let amount = self.builder.iconst(amount);
+        if signed {
+            self.builder.ashr(val, amount, ty).into()
+        } else {
+            self.builder.lshr(val, amount, ty).into()
+        }
+    }
+
+    fn bitfield_immediate(&self, operand: Operand) -> Result<u64, AArch64LifterError> {
+        match operand {
+            Operand::Immediate(imm) => Ok(imm as u64),
+            _ => Err(self.invalid_instruction("expected a bitfield immediate")),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::arm64::lifter::{ClobberModel, Flag, LifterState, INSTRUCTION_SIZE};
+use crate::arm64::{AArch64LifterError, Callee, LiftedCall};
+use tnj::arch::reg::Reg;
+use tnj::types::{BOOL, I64};
+
+/// Registers the callee may modify under AAPCS64, besides the flags
+const CALLER_SAVED_REGS: std::ops::RangeInclusive<u32> = 0..=18;
+
+impl LifterState<'_> {
+    /// Lifts a call as a set of the link register followed by the return edge to `pc + 4`, and
+    /// records the callee on the block of the call
+    pub(crate) fn lift_call(&mut self, pc: u64, callee: Callee) -> Result<(), AArch64LifterError> {
+        self.calls.push(LiftedCall {
+            pc,
+            block: self.builder.current_block(),
+            callee,
+        });
+
+        let return_address = pc + INSTRUCTION_SIZE;
+        let return_block = self.get_block(return_address)?;
+
+        let return_address = self.builder.iconst(return_address);
+        let x30 = self.get_reg_val_by_name("x30");
+        self.write_reg(return_address, x30, I64)?;
+        self.clobber_caller_saved()?;
+
+        self.builder.jump(return_block, vec![]);
+        Ok(())
+    }
+
+    /// Overwrites the registers the callee may modify with opaque values
+    fn clobber_caller_saved(&mut self) -> Result<(), AArch64LifterError> {
+        match self.clobber_model {
+            ClobberModel::None => Ok(()),
+            ClobberModel::Aapcs64 => {
+                for reg in CALLER_SAVED_REGS {
+                    let val = self.builder.opaque(I64);
+                    self.write_reg(val, Reg(reg), I64)?;
+                }
+                for flag in [Flag::N, Flag::Z, Flag::C, Flag::V] {
+                    let val = self.builder.opaque(BOOL);
+                    self.write_flag(val.into(), flag)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use crate::arm64::lifter::{Flag, LifterState};
 use crate::arm64::AArch64LifterError;
-use tnj::air::instructions::Inst;
+use tnj::air::instructions::{Inst, Value};
 use tnj::types::cmp::CmpTy;
-use tnj::types::BOOL;
-use yaxpeax_arm::armv8::a64::Operand;
+use tnj::types::{Type, BOOL};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand};
 
 impl LifterState<'_> {
     pub(crate) fn get_condition(&mut self, operand: Operand) -> Result<Inst, AArch64LifterError> {
@@ -125,4 +125,90 @@ impl LifterState<'_> {
             )),
         }
     }
+
+    /// `a` if `cond` holds, else `b`, selected with a mask instead of a branch
+    pub(crate) fn select(&mut self, cond: impl Into<Value>, a: Value, b: Value, ty: Type) -> Value {
+        let cond = cond.into();
+        if ty == BOOL {
+            let one = self.builder.iconst(1);
+            let not_cond = self.builder.icmp(CmpTy::Ne, cond, one, BOOL);
+            let a = self.builder.and(cond, a, BOOL);
+            let b = self.builder.and(not_cond, b, BOOL);
+            return self.builder.or(a, b, BOOL).into();
+        }
+        // all ones if the condition holds, else zero
+        let zero = self.builder.iconst(0);
+        let cond = self.builder.zext(cond, BOOL, ty);
+        let mask = self.builder.wrapping_sub(zero, cond, ty);
+        let not_mask = self.builder.bitwise_not(mask, ty);
+        let a = self.builder.and(a, mask, ty);
+        let b = self.builder.and(b, not_mask, ty);
+        self.builder.or(a, b, ty).into()
+    }
+
+    /// Lifts `CSEL`, `CSINC`, `CSINV` and `CSNEG` to a select within the current block
+    pub(crate) fn lift_conditional_select(
+        &mut self,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let dst_reg = self.get_dst_reg(inst)?;
+        let op_type = self.get_type_by_inst(inst)?;
+        let condition = self.get_condition(inst.operands[3])?;
+        let src1 = self.get_value(inst.operands[1])?;
+        let src2 = self.get_value(inst.operands[2])?;
+        // the value if the condition is false
+        let src2 = match inst.opcode {
+            Opcode::CSINC => {
+                let one = self.builder.iconst(1);
+                self.builder.wrapping_add(src2, one, op_type).into()
+            }
+            Opcode::CSINV => self.builder.bitwise_not(src2, op_type).into(),
+            Opcode::CSNEG => {
+                let zero = self.builder.iconst(0);
+                self.builder.wrapping_sub(zero, src2, op_type).into()
+            }
+            _ => src2,
+        };
+        let val = self.select(condition, src1, src2, op_type);
+        self.write_reg(val, dst_reg, op_type)
+    }
+
+    /// Lifts `CCMP` and `CCMN` to a select between the flags of the comparison and the `#nzcv`
+    /// immediate within the current block
+    pub(crate) fn lift_conditional_compare(
+        &mut self,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let condition: Value = self.get_condition(inst.operands[3])?.into();
+        let op_type = self.get_type_by_inst(inst)?;
+
+        let mut compared = Vec::new();
+        let src1 = self.get_value(inst.operands[0])?;
+        let src2 = self.get_value(inst.operands[1])?;
+        let src2 = match inst.opcode {
+            Opcode::CCMP => self.builder.bitwise_not(src2, op_type).into(),
+            _ => src2,
+        };
+        let carry = self.builder.iconst(0);
+        self.adc_flags(src1, src2, op_type, carry, &mut |_, value, flag| {
+            compared.push((flag, value));
+            Ok(())
+        })?;
+
+        let mut immediate = Vec::new();
+        let flag_val = self.get_value(inst.operands[2])?;
+        self.flags_of_value(flag_val, op_type, &mut |_, value, flag| {
+            immediate.push((flag, value));
+            Ok(())
+        })?;
+
+        for (flag, imm_val) in immediate {
+            let Some(&(_, cmp_val)) = compared.iter().find(|(f, _)| *f == flag) else {
+                continue;
+            };
+            let val = self.select(condition, cmp_val, imm_val, BOOL);
+            self.write_flag(val, flag)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,160 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::{helper, AArch64LifterError};
+use tnj::air::instructions::Value;
+use tnj::types::cmp::CmpTy;
+use tnj::types::{Type, BOOL, I16, I32, I64, I8};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand};
+
+/// Local exclusive monitor opened by a load-exclusive earlier in the current block
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExclusiveMonitor {
+    /// Address of the reservation
+    address: Value,
+    /// Whether the reservation is still held, cleared by `CLREX` and every store-exclusive
+    valid: Value,
+}
+
+impl LifterState<'_> {
+    /// Whether `opcode` accesses the exclusive monitor
+    pub(crate) fn is_exclusive(opcode: Opcode) -> bool {
+        matches!(
+            opcode,
+            Opcode::LDXR
+                | Opcode::LDAXR
+                | Opcode::LDXRB
+                | Opcode::LDAXRB
+                | Opcode::LDXRH
+                | Opcode::LDAXRH
+                | Opcode::LDXP
+                | Opcode::LDAXP
+                | Opcode::STXR
+                | Opcode::STLXR
+                | Opcode::STXRB
+                | Opcode::STLXRB
+                | Opcode::STXRH
+                | Opcode::STLXRH
+                | Opcode::STXP
+                | Opcode::STLXP
+                | Opcode::CLREX
+        )
+    }
+
+    pub(crate) fn lift_exclusive(&mut self, inst: Instruction) -> Result<(), AArch64LifterError> {
+        match inst.opcode {
+            Opcode::LDXR | Opcode::LDAXR => {
+                let ty = self.get_type_by_inst(inst)?;
+                self.lift_load_exclusive(inst, 1, ty, ty)
+            }
+            Opcode::LDXRB | Opcode::LDAXRB => self.lift_load_exclusive(inst, 1, I8, I32),
+            Opcode::LDXRH | Opcode::LDAXRH => self.lift_load_exclusive(inst, 1, I16, I32),
+            Opcode::LDXP | Opcode::LDAXP => {
+                let ty = self.get_type_by_inst(inst)?;
+                self.lift_load_exclusive(inst, 2, ty, ty)
+            }
+            Opcode::STXR | Opcode::STLXR => {
+                let ty = self.operand_type(inst.operands[1])?;
+                self.lift_store_exclusive(inst, 1, ty)
+            }
+            Opcode::STXRB | Opcode::STLXRB => self.lift_store_exclusive(inst, 1, I8),
+            Opcode::STXRH | Opcode::STLXRH => self.lift_store_exclusive(inst, 1, I16),
+            Opcode::STXP | Opcode::STLXP => {
+                let ty = self.operand_type(inst.operands[1])?;
+                self.lift_store_exclusive(inst, 2, ty)
+            }
+            Opcode::CLREX => {
+                // no address matches a cleared monitor
+                let address = self.builder.iconst(0);
+                let valid = self.builder.iconst(0);
+                self.monitor = Some(ExclusiveMonitor { address, valid });
+                Ok(())
+            }
+            _ => Err(self.invalid_instruction("not an exclusive access")),
+        }
+    }
+
+    /// Loads `count` consecutive values of type `ty` into the first registers and opens the
+    /// monitor on their address
+    fn lift_load_exclusive(
+        &mut self,
+        inst: Instruction,
+        count: usize,
+        ty: Type,
+        reg_ty: Type,
+    ) -> Result<(), AArch64LifterError> {
+        let (address, _) = self.get_address(inst.operands[count])?;
+        for i in 0..count {
+            let dst_reg = self.get_reg_by_index(inst, i)?;
+            let address = self.element_address(address, i, ty);
+            let val: Value = self.builder.load(address, ty).into();
+            let val = if ty == reg_ty {
+                val
+            } else {
+                self.builder.zext(val, ty, reg_ty).into()
+            };
+            self.write_reg(val, dst_reg, reg_ty)?;
+        }
+
+        let valid = self.builder.iconst(1);
+        self.monitor = Some(ExclusiveMonitor { address, valid });
+        Ok(())
+    }
+
+    /// Stores `count` consecutive values only if the monitor holds a reservation for the address,
+    /// and writes 0 to the status register exactly when the store happened. Without a
+    /// load-exclusive earlier in the block the monitor is unknown, and so is whether it stores.
+    fn lift_store_exclusive(
+        &mut self,
+        inst: Instruction,
+        count: usize,
+        ty: Type,
+    ) -> Result<(), AArch64LifterError> {
+        let status_reg = self.get_dst_reg(inst)?;
+        let mut values = Vec::with_capacity(count);
+        for i in 1..=count {
+            let val = self.get_value(inst.operands[i])?;
+            let val = match ty {
+                I8 | I16 => self.builder.trunc(val, I32, ty).into(),
+                _ => val,
+            };
+            values.push(val);
+        }
+        let (address, _) = self.get_address(inst.operands[count + 1])?;
+
+        let success: Value = match self.monitor {
+            Some(monitor) => {
+                let same_address = self.builder.icmp(CmpTy::Eq, monitor.address, address, I64);
+                self.builder.and(monitor.valid, same_address, BOOL).into()
+            }
+            None => self.builder.opaque(BOOL).into(),
+        };
+        for (i, val) in values.into_iter().enumerate() {
+            let address = self.element_address(address, i, ty);
+            // storing the old value back leaves memory unchanged on failure
+            let old = self.builder.load(address, ty).into();
+            let val = self.select(success, val, old, ty);
+            self.builder.store(val, address, ty);
+        }
+
+        // the monitor is cleared by every store-exclusive, successful or not
+        let valid = self.builder.iconst(0);
+        self.monitor = Some(ExclusiveMonitor { address, valid });
+
+        let failure = self.builder.bitwise_not(success, BOOL);
+        let status = self.builder.zext(failure, BOOL, I32);
+        self.write_reg(status, status_reg, I32)
+    }
+
+    fn element_address(&mut self, address: Value, index: usize, ty: Type) -> Value {
+        if index == 0 {
+            return address;
+        }
+        let size = ty.bit_width().expect("type to be bit vector") as u64 / 8;
+        let offset = self.builder.iconst(index as u64 * size);
+        self.builder.wrapping_add(address, offset, I64).into()
+    }
+
+    fn operand_type(&self, operand: Operand) -> Result<Type, AArch64LifterError> {
+        helper::get_type_by_operand(operand)
+            .ok_or_else(|| self.invalid_instruction(format!("operand has no type: {:?}", operand)))
+    }
+}
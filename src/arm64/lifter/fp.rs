@@ -0,0 +1,391 @@
+use crate::arm64::lifter::{Flag, LifterState};
+use crate::arm64::{helper, AArch64LifterError};
+use tnj::air::instructions::Value;
+use tnj::arch::reg::Reg;
+use tnj::types::cmp::CmpTy;
+use tnj::types::{Type, BOOL, F32, F64, I128, I16, I32, I64};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, SIMDSizeCode};
+
+/// Integer type of the bits and float type of a scalar register view
+fn fp_types(sz: SIMDSizeCode) -> Option<(Type, Type)> {
+    match sz {
+        SIMDSizeCode::S => Some((I32, F32)),
+        SIMDSizeCode::D => Some((I64, F64)),
+        _ => None,
+    }
+}
+
+/// Integer type of the bits of a scalar register view
+pub(crate) fn bits_type(sz: SIMDSizeCode) -> Option<Type> {
+    match sz {
+        SIMDSizeCode::H => Some(I16),
+        SIMDSizeCode::S => Some(I32),
+        SIMDSizeCode::D => Some(I64),
+        _ => None,
+    }
+}
+
+impl LifterState<'_> {
+    /// Whether `inst` is a scalar floating-point instruction on the `h`/`s`/`d` register views
+    pub(crate) fn is_scalar_fp(inst: Instruction) -> bool {
+        matches!(
+            inst.opcode,
+            Opcode::FADD
+                | Opcode::FSUB
+                | Opcode::FMUL
+                | Opcode::FNMUL
+                | Opcode::FDIV
+                | Opcode::FMADD
+                | Opcode::FMSUB
+                | Opcode::FNMADD
+                | Opcode::FNMSUB
+                | Opcode::FABS
+                | Opcode::FNEG
+                | Opcode::FSQRT
+                | Opcode::FCVT
+                | Opcode::FMOV
+                | Opcode::FCMP
+                | Opcode::FCMPE
+        ) && inst.operands.iter().all(|op| {
+            matches!(
+                op,
+                Operand::Nothing | Operand::SIMDRegister(..) | Operand::ImmediateDouble(_)
+            )
+        })
+    }
+
+    pub(crate) fn lift_fp(&mut self, pc: u64, inst: Instruction) -> Result<(), AArch64LifterError> {
+        match (inst.opcode, inst.operands) {
+            (
+                Opcode::FADD | Opcode::FSUB | Opcode::FMUL | Opcode::FNMUL | Opcode::FDIV,
+                [Operand::SIMDRegister(sz, d), Operand::SIMDRegister(_, n), Operand::SIMDRegister(_, m), _],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let n = self.read_fp(n, sz)?;
+                let m = self.read_fp(m, sz)?;
+                let val = match inst.opcode {
+                    Opcode::FADD => self.builder.fadd(n, m, ty),
+                    Opcode::FSUB => self.builder.fsub(n, m, ty),
+                    Opcode::FMUL => self.builder.fmul(n, m, ty),
+                    Opcode::FNMUL => {
+                        let val = self.builder.fmul(n, m, ty);
+                        self.builder.fneg(val, ty)
+                    }
+                    _ => self.builder.fdiv(n, m, ty),
+                };
+                self.write_fp(d, sz, val.into())?;
+            }
+            (
+                Opcode::FMADD | Opcode::FMSUB | Opcode::FNMADD | Opcode::FNMSUB,
+                [Operand::SIMDRegister(sz, d), Operand::SIMDRegister(_, n), Operand::SIMDRegister(_, m), Operand::SIMDRegister(_, a)],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let n = self.read_fp(n, sz)?;
+                let m = self.read_fp(m, sz)?;
+                let a = self.read_fp(a, sz)?;
+                // the single rounding of the fused operation is not modelled
+                let product = self.builder.fmul(n, m, ty);
+                let val = match inst.opcode {
+                    // a + n * m
+                    Opcode::FMADD => self.builder.fadd(a, product, ty),
+                    // a - n * m
+                    Opcode::FMSUB => self.builder.fsub(a, product, ty),
+                    // -a - n * m
+                    Opcode::FNMADD => {
+                        let neg_a = self.builder.fneg(a, ty);
+                        self.builder.fsub(neg_a, product, ty)
+                    }
+                    // -a + n * m
+                    _ => {
+                        let neg_a = self.builder.fneg(a, ty);
+                        self.builder.fadd(neg_a, product, ty)
+                    }
+                };
+                self.write_fp(d, sz, val.into())?;
+            }
+            (
+                Opcode::FABS | Opcode::FNEG | Opcode::FSQRT,
+                [Operand::SIMDRegister(sz, d), Operand::SIMDRegister(_, n), ..],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let n = self.read_fp(n, sz)?;
+                let val = match inst.opcode {
+                    Opcode::FABS => self.builder.fabs(n, ty),
+                    Opcode::FNEG => self.builder.fneg(n, ty),
+                    _ => self.builder.fsqrt(n, ty),
+                };
+                self.write_fp(d, sz, val.into())?;
+            }
+            (
+                Opcode::FCVT,
+                [Operand::SIMDRegister(dst_sz, d), Operand::SIMDRegister(src_sz, n), ..],
+            ) => {
+                let (Some((_, dst_ty)), Some((_, src_ty))) = (fp_types(dst_sz), fp_types(src_sz))
+                else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let n = self.read_fp(n, src_sz)?;
+                let val = if dst_ty == F64 {
+                    self.builder.fpext(n, src_ty, dst_ty)
+                } else {
+                    self.builder.fptrunc(n, src_ty, dst_ty)
+                };
+                self.write_fp(d, dst_sz, val.into())?;
+            }
+            (
+                Opcode::SCVTF | Opcode::UCVTF,
+                [Operand::SIMDRegister(sz, d), Operand::Register(int_sz, n), Operand::Nothing, _],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let int_ty = helper::get_type_by_sizecode(int_sz);
+                let n = self.get_value(Operand::Register(int_sz, n))?;
+                let val = if inst.opcode == Opcode::SCVTF {
+                    self.builder.sitofp(n, int_ty, ty)
+                } else {
+                    self.builder.uitofp(n, int_ty, ty)
+                };
+                self.write_fp(d, sz, val.into())?;
+            }
+            (
+                Opcode::FCVTZS | Opcode::FCVTZU,
+                [Operand::Register(int_sz, d), Operand::SIMDRegister(sz, n), Operand::Nothing, _],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let int_ty = helper::get_type_by_sizecode(int_sz);
+                let n = self.read_fp(n, sz)?;
+                // rounds towards zero, saturation of out of range values is not modelled
+                let val = if inst.opcode == Opcode::FCVTZS {
+                    self.builder.fptosi(n, ty, int_ty)
+                } else {
+                    self.builder.fptoui(n, ty, int_ty)
+                };
+                self.write_reg(val, Reg(d as u32), int_ty)?;
+            }
+            (Opcode::FMOV, [dst, src, Operand::Nothing, _]) => {
+                self.lift_fmov(pc, inst, dst, src)?
+            }
+            (
+                Opcode::FCMP | Opcode::FCMPE,
+                [Operand::SIMDRegister(sz, n), m, Operand::Nothing, _],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let n = self.read_fp(n, sz)?;
+                let m = self.read_fp_operand(m, sz)?;
+                let [flag_n, flag_z, flag_c, flag_v] = self.fcmp_flags(n, m, ty);
+                self.write_flag(flag_n, Flag::N)?;
+                self.write_flag(flag_z, Flag::Z)?;
+                self.write_flag(flag_c, Flag::C)?;
+                self.write_flag(flag_v, Flag::V)?;
+            }
+            (
+                Opcode::FCCMP | Opcode::FCCMPE,
+                [Operand::SIMDRegister(sz, n), Operand::SIMDRegister(_, m), Operand::Immediate(nzcv), cond @ Operand::ConditionCode(_)],
+            ) => {
+                let Some((_, ty)) = fp_types(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let cond = self.get_condition(cond)?;
+                let n = self.read_fp(n, sz)?;
+                let m = self.read_fp(m, sz)?;
+                let flags = self.fcmp_flags(n, m, ty);
+                let not_cond = self.builder.bitwise_not(cond, BOOL);
+                // the flags are set by the comparison if the condition holds, else to `nzcv`
+                for (i, (value, flag)) in flags
+                    .into_iter()
+                    .zip([Flag::N, Flag::Z, Flag::C, Flag::V])
+                    .enumerate()
+                {
+                    let imm = self.builder.iconst((nzcv >> (3 - i)) & 1);
+                    let from_cmp = self.builder.and(cond, value, BOOL);
+                    let from_imm = self.builder.and(not_cond, imm, BOOL);
+                    let value = self.builder.or(from_cmp, from_imm, BOOL);
+                    self.write_flag(value.into(), flag)?;
+                }
+            }
+            (
+                Opcode::FCSEL,
+                [Operand::SIMDRegister(sz, d), Operand::SIMDRegister(_, n), Operand::SIMDRegister(_, m), cond @ Operand::ConditionCode(_)],
+            ) => {
+                let Some(ty) = bits_type(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let cond = self.get_condition(cond)?;
+                let n = self.read_fp_bits(n, ty)?;
+                let m = self.read_fp_bits(m, ty)?;
+                let val = self.select(cond, n, m, ty);
+                self.write_fp_bits(d, val, ty)?;
+            }
+            _ => self.lift_opaque(pc, inst)?,
+        }
+        Ok(())
+    }
+
+    /// `FMOV` copies bits between registers and materializes immediates without conversion
+    fn lift_fmov(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+        dst: Operand,
+        src: Operand,
+    ) -> Result<(), AArch64LifterError> {
+        match (dst, src) {
+            (Operand::SIMDRegister(sz, d), Operand::SIMDRegister(_, n)) => {
+                let Some(ty) = bits_type(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let val = self.read_fp_bits(n, ty)?;
+                self.write_fp_bits(d, val, ty)?;
+            }
+            (Operand::SIMDRegister(sz, d), Operand::Register(int_sz, n)) => {
+                let Some(ty) = bits_type(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let val = self.get_value(Operand::Register(int_sz, n))?;
+                let int_ty = helper::get_type_by_sizecode(int_sz);
+                let val = if ty == int_ty {
+                    val
+                } else {
+                    self.builder.trunc(val, int_ty, ty).into()
+                };
+                self.write_fp_bits(d, val, ty)?;
+            }
+            (Operand::Register(int_sz, d), Operand::SIMDRegister(sz, n)) => {
+                let Some(ty) = bits_type(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let int_ty = helper::get_type_by_sizecode(int_sz);
+                let val = self.read_fp_bits(n, ty)?;
+                let val = if ty == int_ty {
+                    val
+                } else {
+                    self.builder.zext(val, ty, int_ty).into()
+                };
+                self.write_reg(val, Reg(d as u32), int_ty)?;
+            }
+            (Operand::SIMDRegister(sz, d), Operand::ImmediateDouble(imm)) => {
+                let Some(ty) = bits_type(sz) else {
+                    return self.lift_opaque(pc, inst);
+                };
+                let val = self.fp_const_bits(imm, sz);
+                self.write_fp_bits(d, val, ty)?;
+            }
+            _ => self.lift_opaque(pc, inst)?,
+        }
+        Ok(())
+    }
+
+    /// NZCV as set by `FCMP`: `1000` if less, `0110` if equal, `0010` if greater, `0011` if
+    /// unordered
+    fn fcmp_flags(&mut self, n: Value, m: Value, ty: Type) -> [Value; 4] {
+        let lt = self.builder.fcmp(CmpTy::Lt, n, m, ty);
+        let eq = self.builder.fcmp(CmpTy::Eq, n, m, ty);
+        let gt = self.builder.fcmp(CmpTy::Gt, n, m, ty);
+        let ordered = self.builder.or(lt, eq, BOOL);
+        let ordered = self.builder.or(ordered, gt, BOOL);
+        let unordered = self.builder.bitwise_not(ordered, BOOL);
+        let not_lt = self.builder.bitwise_not(lt, BOOL);
+        [lt.into(), eq.into(), not_lt.into(), unordered.into()]
+    }
+
+    /// Register holding the vector register `v<n>`, of which `d<n>`, `s<n>` and `h<n>` are views
+    pub(crate) fn simd_reg(&self, n: u16) -> Result<Reg, AArch64LifterError> {
+        self.builder
+            .get_code_region()
+            .get_arch()
+            .lookup_reg(&format!("v{n}").into())
+            .ok_or_else(|| self.invalid_instruction(format!("unknown register v{n}")))
+    }
+
+    /// Reads the low `ty` bits of `v<n>`
+    pub(crate) fn read_fp_bits(&mut self, n: u16, ty: Type) -> Result<Value, AArch64LifterError> {
+        let reg = self.simd_reg(n)?;
+        let val = self.builder.read_reg(reg, I128);
+        if ty == I128 {
+            return Ok(val.into());
+        }
+        Ok(self.builder.trunc(val, I128, ty).into())
+    }
+
+    /// Writes `val` to the low `ty` bits of `v<n>`, clearing the upper bits
+    pub(crate) fn write_fp_bits(
+        &mut self,
+        n: u16,
+        val: Value,
+        ty: Type,
+    ) -> Result<(), AArch64LifterError> {
+        let reg = self.simd_reg(n)?;
+        let val = if ty == I128 {
+            val
+        } else {
+            self.builder.zext(val, ty, I128).into()
+        };
+        self.builder.write_reg(val, reg, I128);
+        Ok(())
+    }
+
+    fn read_fp(&mut self, n: u16, sz: SIMDSizeCode) -> Result<Value, AArch64LifterError> {
+        let (int_ty, ty) = fp_types(sz)
+            .ok_or_else(|| self.invalid_instruction(format!("no float type for {:?}", sz)))?;
+        let bits = self.read_fp_bits(n, int_ty)?;
+        Ok(self.builder.bitcast(bits, int_ty, ty).into())
+    }
+
+    fn read_fp_operand(
+        &mut self,
+        operand: Operand,
+        sz: SIMDSizeCode,
+    ) -> Result<Value, AArch64LifterError> {
+        match operand {
+            Operand::SIMDRegister(_, n) => self.read_fp(n, sz),
+            Operand::ImmediateDouble(imm) => {
+                let (int_ty, ty) = fp_types(sz).ok_or_else(|| {
+                    self.invalid_instruction(format!("no float type for {:?}", sz))
+                })?;
+                let bits = self.fp_const_bits(imm, sz);
+                Ok(self.builder.bitcast(bits, int_ty, ty).into())
+            }
+            op => Err(self.invalid_instruction(format!("invalid float operand {:?}", op))),
+        }
+    }
+
+    fn write_fp(&mut self, d: u16, sz: SIMDSizeCode, val: Value) -> Result<(), AArch64LifterError> {
+        let (int_ty, ty) = fp_types(sz)
+            .ok_or_else(|| self.invalid_instruction(format!("no float type for {:?}", sz)))?;
+        let bits = self.builder.bitcast(val, ty, int_ty);
+        self.write_fp_bits(d, bits.into(), int_ty)
+    }
+
+    /// Bits of an immediate in the format of the register view
+    fn fp_const_bits(&mut self, imm: f64, sz: SIMDSizeCode) -> Value {
+        match sz {
+            SIMDSizeCode::D => self.builder.iconst(imm.to_bits()),
+            SIMDSizeCode::S => self.builder.iconst((imm as f32).to_bits() as u64),
+            _ => self.builder.iconst(f32_to_f16_bits(imm as f32) as u64),
+        }
+    }
+}
+
+/// Half precision bits of `value`; `FMOV` immediates are always exactly representable
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = ((bits >> 13) & 0x3ff) as u16;
+    if value == 0.0 {
+        sign
+    } else {
+        sign | ((exponent as u16) << 10) | mantissa
+    }
+}
@@ -0,0 +1,41 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::AArch64LifterError;
+use tnj::types::cmp::CmpTy;
+use tnj::types::I64;
+use yaxpeax_arm::armv8::a64::Instruction;
+
+impl LifterState<'_> {
+    /// Lifts a `BR` with a recovered jump table to a chain of comparisons against the table
+    /// targets, with an edge to each of them instead of a dynamic jump
+    pub(crate) fn lift_jump_table(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let mut targets = match self.label_resolver.jump_table(pc) {
+            Some(table) => table.targets.clone(),
+            None => return Err(self.invalid_instruction("no jump table")),
+        };
+        // several cases often share a target
+        targets.sort_unstable();
+        targets.dedup();
+
+        let address = self.get_value(inst.operands[0])?;
+        let (last, cases) = targets
+            .split_last()
+            .ok_or_else(|| self.invalid_instruction("empty jump table"))?;
+        for &target in cases {
+            let block = self.get_block(target)?;
+            let next_case = self.builder.create_block("jump_table_case", []);
+            let target = self.builder.iconst(target);
+            let is_target = self.builder.icmp(CmpTy::Eq, address, target, I64);
+            self.builder
+                .jumpif(is_target, block, Vec::new(), next_case, Vec::new());
+            self.builder.set_insert_block(next_case);
+        }
+        // the bounds check guarantees that the address is one of the targets
+        let block = self.get_block(*last)?;
+        self.builder.jump(block, Vec::new());
+        Ok(())
+    }
+}
@@ -0,0 +1,475 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::{helper, AArch64LifterError, ReportKind};
+use tnj::air::instructions::Value;
+use tnj::arch::reg::Reg;
+use tnj::types::cmp::CmpTy;
+use tnj::types::{Type, BOOL, I128, I64, I8};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, SIMDSizeCode};
+
+/// A vector register operand split into lanes, e.g. `v0.4s`
+#[derive(Debug, Clone, Copy)]
+struct Arrangement {
+    reg: u16,
+    /// Size of the vector view, 64 for `D` and 128 for `Q`
+    vec_bits: u32,
+    elem: SIMDSizeCode,
+}
+
+impl Arrangement {
+    fn elem_bits(&self) -> u32 {
+        elem_bits(self.elem)
+    }
+
+    fn elem_type(&self) -> Type {
+        helper::get_type_by_simd_sizecode(self.elem)
+    }
+
+    fn lanes(&self) -> u32 {
+        self.vec_bits / self.elem_bits()
+    }
+}
+
+fn elem_bits(sz: SIMDSizeCode) -> u32 {
+    match sz {
+        SIMDSizeCode::B => 8,
+        SIMDSizeCode::H => 16,
+        SIMDSizeCode::S => 32,
+        SIMDSizeCode::D => 64,
+        SIMDSizeCode::Q => 128,
+    }
+}
+
+fn vec_bits(sz: SIMDSizeCode) -> u32 {
+    if sz == SIMDSizeCode::Q {
+        128
+    } else {
+        64
+    }
+}
+
+fn arrangement(operand: Operand) -> Option<Arrangement> {
+    match operand {
+        Operand::SIMDRegisterElements(vec, reg, elem) => Some(Arrangement {
+            reg,
+            vec_bits: vec_bits(vec),
+            elem,
+        }),
+        _ => None,
+    }
+}
+
+impl LifterState<'_> {
+    /// Whether `inst` is one of the supported integer vector instructions
+    pub(crate) fn is_neon(inst: Instruction) -> bool {
+        let vector = matches!(
+            inst.operands[0],
+            Operand::SIMDRegisterElements(..) | Operand::SIMDRegisterElementsLane(..)
+        );
+        match inst.opcode {
+            Opcode::ADD
+            | Opcode::SUB
+            | Opcode::MUL
+            | Opcode::AND
+            | Opcode::ORR
+            | Opcode::EOR
+            | Opcode::BIC
+            | Opcode::SHL
+            | Opcode::USHR
+            | Opcode::SSHR
+            | Opcode::CMEQ
+            | Opcode::CMGT
+            | Opcode::CMGE
+            | Opcode::CMHI
+            | Opcode::CMHS
+            | Opcode::DUP
+            | Opcode::INS
+            | Opcode::EXT
+            | Opcode::ZIP1
+            | Opcode::ZIP2
+            | Opcode::UZP1
+            | Opcode::UZP2
+            | Opcode::TRN1
+            | Opcode::TRN2
+            | Opcode::TBL => vector,
+            Opcode::UMOV | Opcode::SMOV => {
+                matches!(inst.operands[1], Operand::SIMDRegisterElementsLane(..))
+            }
+            Opcode::ADDV | Opcode::UMAXV => {
+                matches!(inst.operands[1], Operand::SIMDRegisterElements(..))
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn lift_neon(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let [op0, op1, op2, op3] = inst.operands;
+        match inst.opcode {
+            Opcode::ADD | Opcode::SUB | Opcode::MUL => {
+                let (Some(d), Some(n), Some(m)) =
+                    (arrangement(op0), arrangement(op1), arrangement(op2))
+                else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = d.elem_type();
+                let n = self.read_lanes(n)?;
+                let m = self.read_lanes(m)?;
+                let lanes = n
+                    .into_iter()
+                    .zip(m)
+                    .map(|(n, m)| {
+                        let val = match inst.opcode {
+                            Opcode::ADD => self.builder.wrapping_add(n, m, ty),
+                            Opcode::SUB => self.builder.wrapping_sub(n, m, ty),
+                            _ => self.builder.imul(n, m, ty),
+                        };
+                        val.into()
+                    })
+                    .collect();
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::AND | Opcode::ORR | Opcode::EOR | Opcode::BIC => {
+                let (Some(d), Some(n), Some(m)) =
+                    (arrangement(op0), arrangement(op1), arrangement(op2))
+                else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let n = self.read_vec(n.reg)?;
+                let m = self.read_vec(m.reg)?;
+                let val = match inst.opcode {
+                    Opcode::AND => self.builder.and(n, m, I128),
+                    Opcode::ORR => self.builder.or(n, m, I128),
+                    Opcode::EOR => self.builder.xor(n, m, I128),
+                    _ => {
+                        let not_m = self.builder.bitwise_not(m, I128);
+                        self.builder.and(n, not_m, I128)
+                    }
+                };
+                self.write_vec(d, val.into())?;
+            }
+            Opcode::SHL | Opcode::USHR | Opcode::SSHR => {
+                let (Some(d), Some(n), Operand::Immediate(shift)) =
+                    (arrangement(op0), arrangement(op1), op2)
+                else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = d.elem_type();
+                let n = self.read_lanes(n)?;
+                let shift = self.builder.iconst(shift);
+                let lanes = n
+                    .into_iter()
+                    .map(|n| {
+                        let val = match inst.opcode {
+                            Opcode::SHL => self.builder.lshl(n, shift, ty),
+                            Opcode::USHR => self.builder.lshr(n, shift, ty),
+                            _ => self.builder.ashr(n, shift, ty),
+                        };
+                        val.into()
+                    })
+                    .collect();
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::CMEQ | Opcode::CMGT | Opcode::CMGE | Opcode::CMHI | Opcode::CMHS => {
+                let (Some(d), Some(n)) = (arrangement(op0), arrangement(op1)) else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = d.elem_type();
+                let n = self.read_lanes(n)?;
+                // the register form or the comparison against zero
+                let m = match (arrangement(op2), op2) {
+                    (Some(m), _) => self.read_lanes(m)?,
+                    (None, Operand::Immediate(0)) => vec![self.builder.iconst(0); n.len()],
+                    _ => return self.unsupported(pc, inst, ReportKind::SkippedSimd),
+                };
+                let zero = self.builder.iconst(0);
+                let lanes = n
+                    .into_iter()
+                    .zip(m)
+                    .map(|(n, m)| {
+                        let cmp = match inst.opcode {
+                            Opcode::CMEQ => self.builder.icmp(CmpTy::Eq, n, m, ty),
+                            Opcode::CMGT => self.builder.scmp(CmpTy::Gt, n, m, ty),
+                            Opcode::CMGE => self.builder.scmp(CmpTy::Ge, n, m, ty),
+                            Opcode::CMHI => self.builder.ucmp(CmpTy::Gt, n, m, ty),
+                            _ => self.builder.ucmp(CmpTy::Ge, n, m, ty),
+                        };
+                        // all ones if the comparison holds, else zero
+                        let cmp = self.builder.zext(cmp, BOOL, ty);
+                        self.builder.wrapping_sub(zero, cmp, ty).into()
+                    })
+                    .collect();
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::DUP => {
+                let Some(d) = arrangement(op0) else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let val = match op1 {
+                    Operand::SIMDRegisterElementsLane(_, n, elem, lane) => {
+                        let vec = self.read_vec(n)?;
+                        self.get_lane(vec, lane as u32, elem)
+                    }
+                    Operand::Register(sz, n) => {
+                        let val = self.get_value(Operand::Register(sz, n))?;
+                        let int_ty = helper::get_type_by_sizecode(sz);
+                        self.resize(val, int_ty, d.elem_type())
+                    }
+                    _ => return self.unsupported(pc, inst, ReportKind::SkippedSimd),
+                };
+                self.write_lanes(d, vec![val; d.lanes() as usize])?;
+            }
+            Opcode::INS => {
+                let Operand::SIMDRegisterElementsLane(_, d, elem, lane) = op0 else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = helper::get_type_by_simd_sizecode(elem);
+                let val = match op1 {
+                    Operand::SIMDRegisterElementsLane(_, n, src_elem, src_lane) => {
+                        let vec = self.read_vec(n)?;
+                        self.get_lane(vec, src_lane as u32, src_elem)
+                    }
+                    Operand::Register(sz, n) => {
+                        let val = self.get_value(Operand::Register(sz, n))?;
+                        let int_ty = helper::get_type_by_sizecode(sz);
+                        self.resize(val, int_ty, ty)
+                    }
+                    _ => return self.unsupported(pc, inst, ReportKind::SkippedSimd),
+                };
+                // all other lanes, including the upper half, are preserved
+                let bits = elem_bits(elem);
+                let vec = self.read_vec(d)?;
+                let mut lanes: Vec<Value> = (0..128 / bits)
+                    .map(|i| self.get_lane(vec, i, elem))
+                    .collect();
+                lanes[lane as usize] = val;
+                let val = self.build_vec(&lanes, ty);
+                let reg = self.simd_reg(d)?;
+                self.builder.write_reg(val, reg, I128);
+            }
+            Opcode::UMOV | Opcode::SMOV => {
+                let Operand::SIMDRegisterElementsLane(_, n, elem, lane) = op1 else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let Operand::Register(sz, d) = op0 else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = helper::get_type_by_simd_sizecode(elem);
+                let dst_ty = helper::get_type_by_sizecode(sz);
+                let vec = self.read_vec(n)?;
+                let val = self.get_lane(vec, lane as u32, elem);
+                let val = if ty == dst_ty {
+                    val
+                } else if inst.opcode == Opcode::SMOV {
+                    self.builder.sext(val, ty, dst_ty).into()
+                } else {
+                    self.builder.zext(val, ty, dst_ty).into()
+                };
+                self.write_reg(val, Reg(d as u32), dst_ty)?;
+            }
+            Opcode::EXT => {
+                let (Some(d), Some(n), Some(m), Operand::Immediate(index)) =
+                    (arrangement(op0), arrangement(op1), arrangement(op2), op3)
+                else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                // bytes `index..` of the concatenation of n (low) and m (high)
+                let bytes = (d.vec_bits / 8) as usize;
+                let n = self.read_bytes(n.reg, bytes)?;
+                let m = self.read_bytes(m.reg, bytes)?;
+                let lanes = n
+                    .into_iter()
+                    .chain(m)
+                    .skip(index as usize)
+                    .take(bytes)
+                    .collect();
+                let d = Arrangement {
+                    elem: SIMDSizeCode::B,
+                    ..d
+                };
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::ZIP1
+            | Opcode::ZIP2
+            | Opcode::UZP1
+            | Opcode::UZP2
+            | Opcode::TRN1
+            | Opcode::TRN2 => {
+                let (Some(d), Some(n), Some(m)) =
+                    (arrangement(op0), arrangement(op1), arrangement(op2))
+                else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let n = self.read_lanes(n)?;
+                let m = self.read_lanes(m)?;
+                let lanes = d.lanes() as usize;
+                let half = lanes / 2;
+                // index into the concatenation of n and m for each result lane
+                let indices: Vec<usize> = (0..lanes)
+                    .map(|i| match inst.opcode {
+                        Opcode::ZIP1 => i / 2 + (i % 2) * lanes,
+                        Opcode::ZIP2 => half + i / 2 + (i % 2) * lanes,
+                        Opcode::UZP1 => 2 * i,
+                        Opcode::UZP2 => 2 * i + 1,
+                        Opcode::TRN1 => (i & !1) + (i % 2) * lanes,
+                        _ => (i & !1) + 1 + (i % 2) * lanes,
+                    })
+                    .collect();
+                let source: Vec<Value> = n.into_iter().chain(m).collect();
+                let lanes = indices.into_iter().map(|i| source[i]).collect();
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::TBL => {
+                let (Some(d), Operand::SIMDRegisterGroup(_, table, _, 1), Some(m)) =
+                    (arrangement(op0), op1, arrangement(op2))
+                else {
+                    // lookups in tables of more than one register are not modelled
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let table = self.read_vec(table)?;
+                let indices = self.read_lanes(m)?;
+                let eight = self.builder.iconst(8);
+                let sixteen = self.builder.iconst(16);
+                let zero = self.builder.iconst(0);
+                let lanes = indices
+                    .into_iter()
+                    .map(|index| {
+                        let index_128 = self.builder.zext(index, I8, I128);
+                        let shift = self.builder.imul(index_128, eight, I128);
+                        let byte = self.builder.lshr(table, shift, I128);
+                        let byte = self.builder.trunc(byte, I128, I8);
+                        // out of range indices select zero
+                        let in_range = self.builder.ucmp(CmpTy::Lt, index, sixteen, I8);
+                        let mask = self.builder.zext(in_range, BOOL, I8);
+                        let mask = self.builder.wrapping_sub(zero, mask, I8);
+                        self.builder.and(byte, mask, I8).into()
+                    })
+                    .collect();
+                self.write_lanes(d, lanes)?;
+            }
+            Opcode::ADDV | Opcode::UMAXV => {
+                let (Operand::SIMDRegister(_, d), Some(n)) = (op0, arrangement(op1)) else {
+                    return self.unsupported(pc, inst, ReportKind::SkippedSimd);
+                };
+                let ty = n.elem_type();
+                let lanes = self.read_lanes(n)?;
+                let mut acc = lanes[0];
+                for lane in lanes.into_iter().skip(1) {
+                    acc = if inst.opcode == Opcode::ADDV {
+                        self.builder.wrapping_add(acc, lane, ty).into()
+                    } else {
+                        self.umax(acc, lane, ty)
+                    };
+                }
+                self.write_fp_bits(d, acc, ty)?;
+            }
+            _ => self.unsupported(pc, inst, ReportKind::SkippedSimd)?,
+        }
+        Ok(())
+    }
+
+    /// Reads the full 128 bits of `v<n>`
+    pub(crate) fn read_vec(&mut self, n: u16) -> Result<Value, AArch64LifterError> {
+        let reg = self.simd_reg(n)?;
+        Ok(self.builder.read_reg(reg, I128).into())
+    }
+
+    /// Writes a vector, clearing the upper 64 bits for 64-bit arrangements
+    fn write_vec(&mut self, d: Arrangement, val: Value) -> Result<(), AArch64LifterError> {
+        if d.vec_bits == 64 {
+            let val = self.builder.trunc(val, I128, I64);
+            self.write_fp_bits(d.reg, val.into(), I64)
+        } else {
+            let reg = self.simd_reg(d.reg)?;
+            self.builder.write_reg(val, reg, I128);
+            Ok(())
+        }
+    }
+
+    pub(crate) fn get_lane(&mut self, vec: Value, lane: u32, elem: SIMDSizeCode) -> Value {
+        let ty = helper::get_type_by_simd_sizecode(elem);
+        let offset = lane * elem_bits(elem);
+        let lane = if offset == 0 {
+            vec
+        } else {
+            let offset = self.builder.iconst(offset as u64);
+            self.builder.lshr(vec, offset, I128).into()
+        };
+        if ty == I128 {
+            lane
+        } else {
+            self.builder.trunc(lane, I128, ty).into()
+        }
+    }
+
+    fn read_lanes(&mut self, n: Arrangement) -> Result<Vec<Value>, AArch64LifterError> {
+        let vec = self.read_vec(n.reg)?;
+        Ok((0..n.lanes())
+            .map(|i| self.get_lane(vec, i, n.elem))
+            .collect())
+    }
+
+    fn read_bytes(&mut self, n: u16, count: usize) -> Result<Vec<Value>, AArch64LifterError> {
+        let vec = self.read_vec(n)?;
+        Ok((0..count as u32)
+            .map(|i| self.get_lane(vec, i, SIMDSizeCode::B))
+            .collect())
+    }
+
+    fn write_lanes(&mut self, d: Arrangement, lanes: Vec<Value>) -> Result<(), AArch64LifterError> {
+        let val = self.build_vec(&lanes, d.elem_type());
+        let reg = self.simd_reg(d.reg)?;
+        self.builder.write_reg(val, reg, I128);
+        Ok(())
+    }
+
+    /// Concatenates `lanes` starting at the least significant bits, zeroing all bits above
+    pub(crate) fn build_vec(&mut self, lanes: &[Value], ty: Type) -> Value {
+        let bits = ty.bit_width().expect("type to be bit vector") as u64;
+        let mut acc = None;
+        for (i, &lane) in lanes.iter().enumerate() {
+            let lane: Value = if ty == I128 {
+                lane
+            } else {
+                self.builder.zext(lane, ty, I128).into()
+            };
+            let lane = if i == 0 {
+                lane
+            } else {
+                let offset = self.builder.iconst(i as u64 * bits);
+                self.builder.lshl(lane, offset, I128).into()
+            };
+            acc = Some(match acc {
+                Some(acc) => self.builder.or(acc, lane, I128).into(),
+                None => lane,
+            });
+        }
+        acc.unwrap_or_else(|| self.builder.iconst(0))
+    }
+
+    /// Truncates or zero extends a general purpose value to a lane type
+    fn resize(&mut self, val: Value, from: Type, to: Type) -> Value {
+        let from_bits = from.bit_width().expect("type to be bit vector");
+        let to_bits = to.bit_width().expect("type to be bit vector");
+        if from_bits > to_bits {
+            self.builder.trunc(val, from, to).into()
+        } else if from_bits < to_bits {
+            self.builder.zext(val, from, to).into()
+        } else {
+            val
+        }
+    }
+
+    /// Unsigned maximum without control flow: `b ^ ((a ^ b) & mask(a > b))`
+    fn umax(&mut self, a: Value, b: Value, ty: Type) -> Value {
+        let zero = self.builder.iconst(0);
+        let gt = self.builder.ucmp(CmpTy::Gt, a, b, ty);
+        let mask = self.builder.zext(gt, BOOL, ty);
+        let mask = self.builder.wrapping_sub(zero, mask, ty);
+        let diff = self.builder.xor(a, b, ty);
+        let diff = self.builder.and(diff, mask, ty);
+        self.builder.xor(b, diff, ty).into()
+    }
+}
@@ -1,13 +1,14 @@
 use crate::arm64::helper;
 use crate::arm64::lifter::{LifterState, SpOrZrReg};
+use crate::arm64::{AArch64LifterError, ReportKind};
 use tnj::air::instructions::Value;
 use tnj::types::{I16, I32, I64, I8};
 use yaxpeax_arm::armv8::a64::{Operand, ShiftStyle, SizeCode};
 
 impl LifterState<'_> {
     /// Returns the value of a register as a 64-bit value.
-    pub(crate) fn get_value(&mut self, operand: Operand) -> Value {
-        match operand {
+    pub(crate) fn get_value(&mut self, operand: Operand) -> Result<Value, AArch64LifterError> {
+        let value = match operand {
             Operand::Register(sz, reg) | Operand::RegisterOrSP(sz, reg) => {
                 let val = self.reg_val(
                     reg,
@@ -79,27 +80,76 @@ impl LifterState<'_> {
                         self.builder.sext(trunc, I32, I64).into()
                     }
                     ShiftStyle::SXTX => rd,
-                    style => unimplemented!("RegRegOffset with style: {:?}", style),
+                    style => {
+                        return Err(self
+                            .invalid_instruction(format!("RegRegOffset with style: {:?}", style)))
+                    }
                 };
                 self.builder.wrapping_add(rn, offset_val, I64).into()
             }
-            Operand::RegPreIndex(rn, offset, _write_back) => {
+            Operand::RegPreIndex(..) | Operand::RegPostIndex(..) | Operand::RegPostIndexReg(..) => {
+                self.get_address(operand)?.0
+            }
+            Operand::PCOffset(n) => self.builder.iconst(self.pc.wrapping_add(n as u64)),
+            _ => {
+                let ty = helper::get_type_by_operand(operand).ok_or_else(|| {
+                    self.invalid_instruction(format!("unsupported operand: {:?}", operand))
+                })?;
+                // the operand's value is not modelled
+                self.unsupported(self.pc, self.inst, ReportKind::Opaque)?;
+                self.builder.opaque(ty).into()
+            }
+        };
+        Ok(value)
+    }
+
+    /// Returns the address accessed by a memory operand and, for operands with writeback, the
+    /// updated base register value.
+    pub(crate) fn get_address(
+        &mut self,
+        operand: Operand,
+    ) -> Result<(Value, Option<Value>), AArch64LifterError> {
+        let address = match operand {
+            Operand::RegPreIndex(rn, offset, write_back) => {
                 let rn = self.reg_val(rn, SpOrZrReg::Sp);
                 let offset = self.builder.iconst(offset as u64);
-                self.builder.wrapping_add(rn, offset, I64).into()
+                let address: Value = self.builder.wrapping_add(rn, offset, I64).into();
+                (address, write_back.then_some(address))
             }
             Operand::RegPostIndex(rn, offset) => {
-                let val = self.reg_val(rn, SpOrZrReg::Sp);
+                let address = self.reg_val(rn, SpOrZrReg::Sp);
                 let offset = self.builder.iconst(offset as u64);
-                self.builder.wrapping_add(val, offset, I64).into()
+                let base: Value = self.builder.wrapping_add(address, offset, I64).into();
+                (address, Some(base))
+            }
+            Operand::RegPostIndexReg(rn, rm) => {
+                let address = self.reg_val(rn, SpOrZrReg::Sp);
+                let offset = self.reg_val(rm, SpOrZrReg::Zr);
+                let base: Value = self.builder.wrapping_add(address, offset, I64).into();
+                (address, Some(base))
             }
-            Operand::RegPostIndexReg(_, _) => unimplemented!("RegPostIndexReg"),
-            Operand::PCOffset(n) => self.builder.iconst(n as u64),
-            _ => self
-                .builder
-                .opaque(helper::get_type_by_operand(operand))
-                .into(),
+            _ => (self.get_value(operand)?, None),
+        };
+        Ok(address)
+    }
+
+    /// Writes the updated base register returned by [`Self::get_address`], if any.
+    pub(crate) fn write_back(
+        &mut self,
+        operand: Operand,
+        base: Option<Value>,
+    ) -> Result<(), AArch64LifterError> {
+        let rn = match operand {
+            Operand::RegPreIndex(rn, ..)
+            | Operand::RegPostIndex(rn, _)
+            | Operand::RegPostIndexReg(rn, _) => rn,
+            _ => return Ok(()),
+        };
+        if let Some(base) = base {
+            let reg = self.reg_or_sp(rn);
+            self.write_reg(base, reg, I64)?;
         }
+        Ok(())
     }
 
     pub fn is_simd_register(operand: Operand) -> bool {
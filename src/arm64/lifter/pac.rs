@@ -0,0 +1,225 @@
+use crate::arm64::lifter::{CallMode, LifterState, SpOrZrReg, INSTRUCTION_SIZE};
+use crate::arm64::{helper, AArch64LifterError, Callee};
+use tnj::air::instructions::Value;
+use tnj::arch::reg::Reg;
+use tnj::types::{BOOL, I64};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand};
+
+/// Upper bits of a pointer, which hold its authentication code and copies of bit 55, assuming
+/// 48-bit virtual addresses
+const PAC_MASK: u64 = 0xffff_0000_0000_0000;
+
+/// Bits of a pointer holding its authentication code, all upper bits except bit 55 which selects
+/// the upper or lower address range
+const CODE_MASK: u64 = 0xff7f_0000_0000_0000;
+
+const X17: u16 = 17;
+const LR: u16 = 30;
+
+/// Pointer authentication instruction encoded in the `HINT` space, with the register holding the
+/// pointer
+#[derive(Debug, Clone, Copy)]
+enum PacHint {
+    Sign(u16),
+    Auth(u16),
+    Strip(u16),
+}
+
+fn pac_hint(imm: u32) -> Option<PacHint> {
+    let hint = match imm {
+        // XPACLRI
+        0x07 => PacHint::Strip(LR),
+        // PACIA1716, PACIB1716
+        0x08 | 0x0a => PacHint::Sign(X17),
+        // AUTIA1716, AUTIB1716
+        0x0c | 0x0e => PacHint::Auth(X17),
+        // PACIAZ, PACIASP, PACIBZ, PACIBSP
+        0x18..=0x1b => PacHint::Sign(LR),
+        // AUTIAZ, AUTIASP, AUTIBZ, AUTIBSP
+        0x1c..=0x1f => PacHint::Auth(LR),
+        _ => return None,
+    };
+    Some(hint)
+}
+
+impl LifterState<'_> {
+    /// Whether `opcode` signs, authenticates or strips a pointer, or branches to an
+    /// authenticated one
+    pub(crate) fn is_pac(opcode: Opcode) -> bool {
+        Self::is_pac_branch(opcode)
+            || matches!(
+                opcode,
+                Opcode::PACIA
+                    | Opcode::PACIB
+                    | Opcode::PACDA
+                    | Opcode::PACDB
+                    | Opcode::PACIZA
+                    | Opcode::PACIZB
+                    | Opcode::PACDZA
+                    | Opcode::PACDZB
+                    | Opcode::AUTIA
+                    | Opcode::AUTIB
+                    | Opcode::AUTDA
+                    | Opcode::AUTDB
+                    | Opcode::AUTIZA
+                    | Opcode::AUTIZB
+                    | Opcode::AUTDZA
+                    | Opcode::AUTDZB
+                    | Opcode::XPACI
+                    | Opcode::XPACD
+            )
+    }
+
+    /// Whether `opcode` branches to an authenticated pointer
+    pub(crate) fn is_pac_branch(opcode: Opcode) -> bool {
+        matches!(
+            opcode,
+            Opcode::BRAA
+                | Opcode::BRAB
+                | Opcode::BRAAZ
+                | Opcode::BRABZ
+                | Opcode::BLRAA
+                | Opcode::BLRAB
+                | Opcode::BLRAAZ
+                | Opcode::BLRABZ
+                | Opcode::RETAA
+                | Opcode::RETAB
+        )
+    }
+
+    pub(crate) fn lift_pac(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let d = match (inst.opcode, inst.operands[0]) {
+            (Opcode::RETAA | Opcode::RETAB, _) => LR,
+            (_, Operand::Register(_, reg) | Operand::RegisterOrSP(_, reg)) => reg,
+            _ => return Err(self.invalid_instruction("expected a pointer register")),
+        };
+
+        if matches!(inst.opcode, Opcode::XPACI | Opcode::XPACD) {
+            return self.pac_strip(d);
+        }
+
+        match inst.opcode {
+            Opcode::PACIA
+            | Opcode::PACIB
+            | Opcode::PACDA
+            | Opcode::PACDB
+            | Opcode::PACIZA
+            | Opcode::PACIZB
+            | Opcode::PACDZA
+            | Opcode::PACDZB => self.pac_sign(d)?,
+            Opcode::AUTIA
+            | Opcode::AUTIB
+            | Opcode::AUTDA
+            | Opcode::AUTDB
+            | Opcode::AUTIZA
+            | Opcode::AUTIZB
+            | Opcode::AUTDZA
+            | Opcode::AUTDZB => {
+                let ptr = self.pac_authenticate(d);
+                self.write_pointer(d, ptr)?;
+            }
+            Opcode::BRAA | Opcode::BRAB | Opcode::BRAAZ | Opcode::BRABZ => {
+                let target = self.pac_authenticate(d);
+                self.builder.dynamic_jump(target);
+            }
+            Opcode::BLRAA | Opcode::BLRAB | Opcode::BLRAAZ | Opcode::BLRABZ => {
+                let target = self.pac_authenticate(d);
+                if self.call_mode == CallMode::Call {
+                    self.lift_call(pc, Callee::Indirect(target))?;
+                } else {
+                    let ret_address = self.builder.iconst(pc + INSTRUCTION_SIZE);
+                    self.write_pointer(LR, ret_address)?;
+                    self.builder.dynamic_jump(target);
+                    self.mark_next_block_as_entry(pc);
+                }
+            }
+            Opcode::RETAA | Opcode::RETAB => {
+                let target = self.pac_authenticate(LR);
+                self.builder.dynamic_jump(target);
+            }
+            _ => return Err(self.invalid_instruction("not a pointer authentication instruction")),
+        }
+        Ok(())
+    }
+
+    /// Lifts a `HINT`: pointer authentication hints get explicit sign and authenticate operations,
+    /// `BTI` landing pads become entries, hints without dataflow effect lift to nothing, and all
+    /// other hints are ignored
+    pub(crate) fn lift_hint(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let imm = helper::get_hint_immediate(inst);
+        match imm.and_then(pac_hint) {
+            Some(PacHint::Sign(d)) => self.pac_sign(d)?,
+            Some(PacHint::Auth(d)) => {
+                let ptr = self.pac_authenticate(d);
+                self.write_pointer(d, ptr)?;
+            }
+            Some(PacHint::Strip(d)) => self.pac_strip(d)?,
+            None if helper::is_landing_pad(inst) => {
+                // indirect branches may enter the code here
+                if let Some(block) = self.label_resolver.get_block(pc) {
+                    self.builder.mark_entry_block(block);
+                }
+            }
+            None if helper::is_nop_hint(inst) => {}
+            None => self.ignored(pc, inst)?,
+        }
+        Ok(())
+    }
+
+    /// Replaces the code bits of the pointer in `d` with its authentication code. The hardware
+    /// computes the code from the pointer, a modifier and a secret key the lifted code cannot
+    /// observe, so the code is opaque.
+    fn pac_sign(&mut self, d: u16) -> Result<(), AArch64LifterError> {
+        let ptr = self.reg_val(d, SpOrZrReg::Zr);
+        let addr_mask = self.builder.iconst(!CODE_MASK);
+        let addr = self.builder.and(ptr, addr_mask, I64);
+        let code = self.builder.opaque(I64);
+        let code_mask = self.builder.iconst(CODE_MASK);
+        let code = self.builder.and(code, code_mask, I64);
+        let signed = self.builder.or(addr, code, I64);
+        self.write_pointer(d, signed.into())
+    }
+
+    /// Traps if the authentication code of the pointer in `d` is wrong, and returns the pointer
+    /// without it. Whether the code is right depends on the secret key, so the check may fail for
+    /// any pointer.
+    fn pac_authenticate(&mut self, d: u16) -> Value {
+        let ptr = self.reg_val(d, SpOrZrReg::Zr);
+        let addr = self.pac_address(ptr);
+        let failed = self.builder.opaque(BOOL);
+        self.builder.trapif(failed);
+        addr
+    }
+
+    /// Removes the authentication code of the pointer in `d` without checking it
+    fn pac_strip(&mut self, d: u16) -> Result<(), AArch64LifterError> {
+        let ptr = self.reg_val(d, SpOrZrReg::Zr);
+        let addr = self.pac_address(ptr);
+        self.write_pointer(d, addr)
+    }
+
+    /// Pointer without its authentication code: the upper bits are copies of bit 55
+    fn pac_address(&mut self, ptr: Value) -> Value {
+        let shift = self.builder.iconst(8);
+        let bit_55 = self.builder.lshl(ptr, shift, I64);
+        let shift = self.builder.iconst(63);
+        let extension = self.builder.ashr(bit_55, shift, I64);
+        let upper_mask = self.builder.iconst(PAC_MASK);
+        let upper = self.builder.and(extension, upper_mask, I64);
+        let addr_mask = self.builder.iconst(!PAC_MASK);
+        let addr = self.builder.and(ptr, addr_mask, I64);
+        self.builder.or(addr, upper, I64).into()
+    }
+
+    fn write_pointer(&mut self, d: u16, val: Value) -> Result<(), AArch64LifterError> {
+        self.write_reg(val, Reg(d as u32), I64)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::arm64::lifter::{LifterState, SpOrZrReg};
+use crate::arm64::AArch64LifterError;
 use tnj::air::instructions::Value;
 use tnj::arch::reg::Reg;
 use tnj::types::{Type, I64};
@@ -27,35 +28,50 @@ impl LifterState<'_> {
         }
     }
 
-    pub(crate) fn get_reg_by_index(&self, inst: Instruction, index: usize) -> Option<Reg> {
-        let dst_reg = match inst.operands[index] {
-            Operand::Register(_, reg) => Some(Reg(reg as u32)),
+    /// Register of the architecture named `name`, for state beyond the general purpose registers,
+    /// or `None` if the architecture does not model it
+    pub(crate) fn named_reg(&self, name: &str) -> Option<Reg> {
+        self.builder
+            .get_code_region()
+            .get_arch()
+            .lookup_reg(&name.into())
+    }
+
+    pub(crate) fn get_reg_by_index(
+        &self,
+        inst: Instruction,
+        index: usize,
+    ) -> Result<Reg, AArch64LifterError> {
+        match inst.operands[index] {
+            Operand::Register(_, reg) => Ok(Reg(reg as u32)),
             Operand::RegisterOrSP(sz, reg) => {
-                if reg == 31 {
-                    assert_eq!(sz, SizeCode::X, "sp must be 64 bits");
-                    Some(
-                        self.builder
-                            .get_code_region()
-                            .get_arch()
-                            .lookup_reg(&"sp".into())
-                            .unwrap(),
-                    )
-                } else {
-                    Some(Reg(reg as u32))
+                if reg == 31 && sz != SizeCode::X {
+                    return Err(self.invalid_instruction("sp must be 64 bits"));
                 }
+                Ok(self.reg_or_sp(reg))
             }
-            _ => None,
-        };
-        dst_reg
+            op => Err(self.invalid_instruction(format!(
+                "operand {} is not a general purpose register: {:?}",
+                index, op
+            ))),
+        }
     }
 
-    pub(crate) fn get_dst_reg(&self, inst: Instruction) -> Option<Reg> {
-        self.get_reg_by_index(inst, 0)
+    /// Returns the register with index `reg`, where 31 refers to sp
+    pub(crate) fn reg_or_sp(&self, reg: u16) -> Reg {
+        if reg == 31 {
+            self.builder
+                .get_code_region()
+                .get_arch()
+                .lookup_reg(&"sp".into())
+                .unwrap()
+        } else {
+            Reg(reg as u32)
+        }
     }
 
-    pub(crate) fn read_pc_reg(&mut self) -> Value {
-        let reg = self.get_reg_val_by_name("pc");
-        self.reg_val(reg.0 as u16, SpOrZrReg::Sp)
+    pub(crate) fn get_dst_reg(&self, inst: Instruction) -> Result<Reg, AArch64LifterError> {
+        self.get_reg_by_index(inst, 0)
     }
 
     pub(crate) fn get_reg_val_by_name(&mut self, name: &str) -> Reg {
@@ -66,8 +82,19 @@ impl LifterState<'_> {
             .unwrap()
     }
 
-    pub(crate) fn write_reg(&mut self, val: impl Into<Value>, dst_reg: Reg, op_type: Type) {
-        assert_ne!(dst_reg.0, 31, "cannot write to register 31");
+    /// Writes a general purpose register. Writes to register 31 are discarded, since it is the
+    /// zero register wherever it is not the stack pointer, which is written via
+    /// [`LifterState::reg_or_sp`].
+    pub(crate) fn write_reg(
+        &mut self,
+        val: impl Into<Value>,
+        dst_reg: Reg,
+        op_type: Type,
+    ) -> Result<(), AArch64LifterError> {
+        if dst_reg.0 == 31 {
+            return Ok(());
+        }
         self.builder.write_reg(val, dst_reg, op_type);
+        Ok(())
     }
 }
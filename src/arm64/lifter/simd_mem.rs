@@ -0,0 +1,174 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::{helper, AArch64LifterError, ReportKind};
+use tnj::air::instructions::Value;
+use tnj::types::{I128, I64};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, SIMDSizeCode};
+
+impl LifterState<'_> {
+    /// Whether `inst` transfers FP/SIMD registers to or from memory
+    pub(crate) fn is_simd_transfer(inst: Instruction) -> bool {
+        match inst.opcode {
+            Opcode::LDR
+            | Opcode::LDUR
+            | Opcode::STR
+            | Opcode::STUR
+            | Opcode::LDP
+            | Opcode::LDNP
+            | Opcode::STP
+            | Opcode::STNP => matches!(inst.operands[0], Operand::SIMDRegister(..)),
+            Opcode::LD1
+            | Opcode::LD2
+            | Opcode::LD3
+            | Opcode::LD4
+            | Opcode::ST1
+            | Opcode::ST2
+            | Opcode::ST3
+            | Opcode::ST4 => matches!(inst.operands[0], Operand::SIMDRegisterGroup(..)),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn lift_simd_transfer(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        match (inst.opcode, inst.operands) {
+            (Opcode::LDR | Opcode::LDUR, [Operand::SIMDRegister(sz, t), mem, ..]) => {
+                let ty = helper::get_type_by_simd_sizecode(sz);
+                let (address, base) = self.get_address(mem)?;
+                let val = self.builder.load(address, ty);
+                self.write_fp_bits(t, val.into(), ty)?;
+                self.write_back(mem, base)?;
+            }
+            (Opcode::STR | Opcode::STUR, [Operand::SIMDRegister(sz, t), mem, ..]) => {
+                let ty = helper::get_type_by_simd_sizecode(sz);
+                let val = self.read_fp_bits(t, ty)?;
+                let (address, base) = self.get_address(mem)?;
+                self.builder.store(val, address, ty);
+                self.write_back(mem, base)?;
+            }
+            (
+                Opcode::LDP | Opcode::LDNP,
+                [Operand::SIMDRegister(sz, t1), Operand::SIMDRegister(_, t2), mem, _],
+            ) => {
+                let ty = helper::get_type_by_simd_sizecode(sz);
+                let (address, base) = self.get_address(mem)?;
+                let val1 = self.builder.load(address, ty);
+                self.write_fp_bits(t1, val1.into(), ty)?;
+                let address = self.offset_address(address, simd_bytes(sz));
+                let val2 = self.builder.load(address, ty);
+                self.write_fp_bits(t2, val2.into(), ty)?;
+                self.write_back(mem, base)?;
+            }
+            (
+                Opcode::STP | Opcode::STNP,
+                [Operand::SIMDRegister(sz, t1), Operand::SIMDRegister(_, t2), mem, _],
+            ) => {
+                let ty = helper::get_type_by_simd_sizecode(sz);
+                let val1 = self.read_fp_bits(t1, ty)?;
+                let val2 = self.read_fp_bits(t2, ty)?;
+                let (address, base) = self.get_address(mem)?;
+                self.builder.store(val1, address, ty);
+                let address = self.offset_address(address, simd_bytes(sz));
+                self.builder.store(val2, address, ty);
+                self.write_back(mem, base)?;
+            }
+            (_, [Operand::SIMDRegisterGroup(vec_sz, first, elem, count), mem, ..]) => {
+                self.lift_structures(inst.opcode, vec_sz, first, elem, count, mem)?;
+            }
+            _ => self.unsupported(pc, inst, ReportKind::SkippedSimd)?,
+        }
+        Ok(())
+    }
+
+    /// `LD1`–`LD4` and `ST1`–`ST4` with multiple structures, where `LDn` de-interleaves
+    /// consecutive elements into `n` registers
+    fn lift_structures(
+        &mut self,
+        opcode: Opcode,
+        vec_sz: SIMDSizeCode,
+        first: u16,
+        elem: SIMDSizeCode,
+        count: u8,
+        mem: Operand,
+    ) -> Result<(), AArch64LifterError> {
+        let is_load = matches!(
+            opcode,
+            Opcode::LD1 | Opcode::LD2 | Opcode::LD3 | Opcode::LD4
+        );
+        let structures = match opcode {
+            Opcode::LD1 | Opcode::ST1 => 1,
+            Opcode::LD2 | Opcode::ST2 => 2,
+            Opcode::LD3 | Opcode::ST3 => 3,
+            _ => 4,
+        };
+        // the register list wraps around from v31 to v0
+        let regs: Vec<u16> = (0..count as u16).map(|i| (first + i) % 32).collect();
+        let (address, base) = self.get_address(mem)?;
+
+        if structures == 1 {
+            let (ty, bytes) = if vec_sz == SIMDSizeCode::Q {
+                (I128, 16)
+            } else {
+                (I64, 8)
+            };
+            for (i, &reg) in regs.iter().enumerate() {
+                let address = self.offset_address(address, i as u64 * bytes);
+                if is_load {
+                    let val = self.builder.load(address, ty);
+                    self.write_fp_bits(reg, val.into(), ty)?;
+                } else {
+                    let val = self.read_fp_bits(reg, ty)?;
+                    self.builder.store(val, address, ty);
+                }
+            }
+        } else {
+            let ty = helper::get_type_by_simd_sizecode(elem);
+            let elem_bytes = simd_bytes(elem);
+            let lanes = simd_bytes(vec_sz) / elem_bytes;
+            for (r, &reg) in regs.iter().enumerate() {
+                // lane j of register r is element j * structures + r in memory
+                let offset = |j: u64| (j * structures + r as u64) * elem_bytes;
+                if is_load {
+                    let values: Vec<Value> = (0..lanes)
+                        .map(|j| {
+                            let address = self.offset_address(address, offset(j));
+                            self.builder.load(address, ty).into()
+                        })
+                        .collect();
+                    let val = self.build_vec(&values, ty);
+                    self.write_fp_bits(reg, val, I128)?;
+                } else {
+                    let vec = self.read_vec(reg)?;
+                    for j in 0..lanes {
+                        let val = self.get_lane(vec, j as u32, elem);
+                        let address = self.offset_address(address, offset(j));
+                        self.builder.store(val, address, ty);
+                    }
+                }
+            }
+        }
+
+        self.write_back(mem, base)
+    }
+
+    fn offset_address(&mut self, address: Value, offset: u64) -> Value {
+        if offset == 0 {
+            return address;
+        }
+        let offset = self.builder.iconst(offset);
+        self.builder.wrapping_add(address, offset, I64).into()
+    }
+}
+
+/// Size in bytes of a register view or vector arrangement
+fn simd_bytes(sz: SIMDSizeCode) -> u64 {
+    match sz {
+        SIMDSizeCode::B => 1,
+        SIMDSizeCode::H => 2,
+        SIMDSizeCode::S => 4,
+        SIMDSizeCode::D => 8,
+        SIMDSizeCode::Q => 16,
+    }
+}
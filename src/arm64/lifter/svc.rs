@@ -0,0 +1,71 @@
+use crate::arm64::lifter::{LifterState, SpOrZrReg, SyscallMode};
+use crate::arm64::{AArch64LifterError, Syscall};
+use tnj::arch::reg::Reg;
+use tnj::types::I64;
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand};
+
+/// Register holding the system call number
+const SYSCALL_NUMBER_REG: u16 = 8;
+
+/// Registers the kernel reads: the number followed by the arguments
+const SYSCALL_INPUT_REGS: [u16; 7] = [SYSCALL_NUMBER_REG, 0, 1, 2, 3, 4, 5];
+
+/// Register receiving the result of the system call
+const SYSCALL_RESULT_REG: u32 = 0;
+
+impl LifterState<'_> {
+    /// Lifts `SVC #imm` according to the syscall mode
+    pub(crate) fn lift_svc(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        // the kernel may clear the exclusive monitor
+        self.monitor = None;
+        if self.syscall_mode == SyscallMode::Ignore {
+            self.ignored(pc, inst)?;
+            self.mark_next_block_as_entry(pc);
+            return Ok(());
+        }
+
+        let imm = match inst.operands[0] {
+            Operand::Immediate(imm) => imm as u64,
+            _ => return Err(self.invalid_instruction("expected an immediate")),
+        };
+
+        // the kernel observes the number and all argument registers
+        let inputs = SYSCALL_INPUT_REGS.map(|reg| self.reg_val(reg, SpOrZrReg::Zr));
+        let result = self.builder.opaque(I64);
+        self.write_reg(result, Reg(SYSCALL_RESULT_REG), I64)?;
+
+        self.syscalls.push(Syscall {
+            pc,
+            block: self.builder.current_block(),
+            imm,
+            number: self.syscall_number,
+            inputs,
+            name: None,
+        });
+        Ok(())
+    }
+
+    /// Follows the constant in `x8` within a block so that a later `SVC` knows its number. Any
+    /// other instruction with `x8` as its first or second operand, such as `ldp x7, x8, [sp]`, and
+    /// any call may overwrite it, and forgets the number.
+    pub(crate) fn track_syscall_number(&mut self, inst: Instruction) {
+        match (inst.opcode, inst.operands[0], inst.operands[1]) {
+            (Opcode::MOVZ, Operand::Register(_, SYSCALL_NUMBER_REG), Operand::ImmShift(n, s)) => {
+                self.syscall_number = Some((n as u64) << (s as u64));
+            }
+            (Opcode::MOVZ, Operand::Register(_, SYSCALL_NUMBER_REG), Operand::Immediate(n)) => {
+                self.syscall_number = Some(n as u64);
+            }
+            (Opcode::BL | Opcode::BLR, _, _) => self.syscall_number = None,
+            (opcode, _, _) if Self::is_pac_branch(opcode) => self.syscall_number = None,
+            (_, Operand::Register(_, SYSCALL_NUMBER_REG), _)
+            | (_, _, Operand::Register(_, SYSCALL_NUMBER_REG)) => self.syscall_number = None,
+            // the result of a system call overwrites only x0
+            _ => {}
+        }
+    }
+}
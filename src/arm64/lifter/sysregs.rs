@@ -0,0 +1,110 @@
+use crate::arm64::lifter::{Flag, LifterState};
+use crate::arm64::{AArch64LifterError, ReportKind};
+use tnj::air::instructions::Value;
+use tnj::arch::reg::Reg;
+use tnj::types::{BOOL, I64};
+use yaxpeax_arm::armv8::a64::{Instruction, Opcode, Operand, SizeCode};
+
+/// Encoding of a system register operand as decoded by yaxpeax: the low bit of `op0` followed by
+/// `op1`, `CRn`, `CRm` and `op2`
+const fn sysreg(op0: u16, op1: u16, crn: u16, crm: u16, op2: u16) -> u16 {
+    ((op0 & 1) << 14) | (op1 << 11) | (crn << 7) | (crm << 3) | op2
+}
+
+const NZCV: u16 = sysreg(3, 3, 4, 2, 0);
+
+/// Architectural registers accessible through `MRS`/`MSR`, by the name of their AIR register
+const SYSTEM_REGISTERS: [(u16, &str); 10] = [
+    (sysreg(3, 3, 4, 4, 0), "fpcr"),
+    (sysreg(3, 3, 4, 4, 1), "fpsr"),
+    (sysreg(3, 3, 13, 0, 2), "tpidr_el0"),
+    (sysreg(3, 3, 13, 0, 3), "tpidrro_el0"),
+    (sysreg(3, 3, 14, 0, 0), "cntfrq_el0"),
+    (sysreg(3, 3, 14, 0, 1), "cntpct_el0"),
+    (sysreg(3, 3, 14, 0, 2), "cntvct_el0"),
+    (sysreg(3, 3, 0, 0, 1), "ctr_el0"),
+    (sysreg(3, 3, 0, 0, 7), "dczid_el0"),
+    (sysreg(3, 0, 0, 0, 0), "midr_el1"),
+];
+
+/// Generic `s<op0>_<op1>_c<n>_c<m>_<op2>` name of a system register without an architectural name
+fn generic_register_name(encoding: u16) -> String {
+    let op0 = 2 + (encoding >> 14);
+    let op1 = (encoding >> 11) & 0b111;
+    let crn = (encoding >> 7) & 0b1111;
+    let crm = (encoding >> 3) & 0b1111;
+    let op2 = encoding & 0b111;
+    format!("s{op0}_{op1}_c{crn}_c{crm}_{op2}")
+}
+
+/// Bit of each flag in `NZCV`
+const NZCV_BITS: [(Flag, u64); 4] = [(Flag::N, 31), (Flag::Z, 30), (Flag::C, 29), (Flag::V, 28)];
+
+impl LifterState<'_> {
+    /// Lifts `MRS` and `MSR` to accesses of the named system register, or of the flags for `NZCV`
+    pub(crate) fn lift_system_register(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        match (inst.opcode, inst.operands) {
+            (Opcode::MRS, [Operand::Register(SizeCode::X, t), Operand::SystemReg(NZCV), ..]) => {
+                let mut val = self.builder.iconst(0);
+                for (flag, bit) in NZCV_BITS {
+                    let flag = self.flag_value(flag);
+                    let flag = self.builder.zext(flag, BOOL, I64);
+                    let bit = self.builder.iconst(bit);
+                    let flag = self.builder.lshl(flag, bit, I64);
+                    val = self.builder.or(val, flag, I64).into();
+                }
+                self.write_system_result(t, val)?;
+            }
+            (Opcode::MSR, [Operand::SystemReg(NZCV), Operand::Register(SizeCode::X, t), ..]) => {
+                let val = self.get_value(Operand::Register(SizeCode::X, t))?;
+                let shift = self.builder.iconst(28);
+                let val = self.builder.lshr(val, shift, I64);
+                self.set_flags_to_value(val.into(), I64)?;
+            }
+            (Opcode::MRS, [Operand::Register(SizeCode::X, t), Operand::SystemReg(sysreg), ..]) => {
+                match self.system_register(sysreg) {
+                    Some(reg) => {
+                        let val = self.builder.read_reg(reg, I64).into();
+                        self.write_system_result(t, val)?;
+                    }
+                    None => self.lift_opaque(pc, inst)?,
+                }
+            }
+            (Opcode::MSR, [Operand::SystemReg(sysreg), Operand::Register(SizeCode::X, t), ..]) => {
+                match self.system_register(sysreg) {
+                    Some(reg) => {
+                        let val = self.get_value(Operand::Register(SizeCode::X, t))?;
+                        self.builder.write_reg(val, reg, I64);
+                    }
+                    None => self.unsupported(pc, inst, ReportKind::Opaque)?,
+                }
+            }
+            // MSR with an immediate only changes PSTATE fields such as the interrupt masks, which
+            // have no effect on the lifted dataflow
+            (Opcode::MSR, [op, ..]) if !matches!(op, Operand::SystemReg(_)) => {}
+            _ => self.ignored(pc, inst)?,
+        }
+        Ok(())
+    }
+
+    /// AIR register of a system register by its architectural or generic name, `None` if the
+    /// architecture does not model it
+    fn system_register(&self, encoding: u16) -> Option<Reg> {
+        match SYSTEM_REGISTERS.iter().find(|(enc, _)| *enc == encoding) {
+            Some((_, name)) => self.named_reg(name),
+            None => self.named_reg(&generic_register_name(encoding)),
+        }
+    }
+
+    /// `MRS` into the zero register discards the value
+    fn write_system_result(&mut self, t: u16, val: Value) -> Result<(), AArch64LifterError> {
+        if t == 31 {
+            return Ok(());
+        }
+        self.write_reg(val, Reg(t as u32), I64)
+    }
+}
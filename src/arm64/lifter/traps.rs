@@ -0,0 +1,30 @@
+use crate::arm64::lifter::LifterState;
+use crate::arm64::{AArch64LifterError, TrapKind, TrapSite};
+use yaxpeax_arm::armv8::a64::{Instruction, Operand};
+
+impl LifterState<'_> {
+    /// Lifts `BRK #imm`, `HLT #imm` and `UDF #imm` to a trap that ends the block, recording the
+    /// immediate as its trap code
+    pub(crate) fn lift_trap(
+        &mut self,
+        pc: u64,
+        inst: Instruction,
+    ) -> Result<(), AArch64LifterError> {
+        let Some(kind) = TrapKind::of_opcode(inst.opcode) else {
+            return Err(self.invalid_instruction("not a trap"));
+        };
+        let code = match inst.operands[0] {
+            Operand::Immediate(imm) => imm as u16,
+            _ => return Err(self.invalid_instruction("expected a trap code")),
+        };
+
+        self.builder.trap();
+        self.traps.push(TrapSite {
+            pc,
+            block: self.builder.current_block(),
+            kind,
+            code,
+        });
+        Ok(())
+    }
+}
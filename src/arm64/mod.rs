@@ -1,8 +1,30 @@
 //! Contains the lifter for arm64.
 
+mod call_graph;
+mod calls;
+mod cfg;
+mod dot;
+mod eh_frame;
+mod elf_lifter;
+mod function_discovery;
 mod helper;
+mod jump_table;
 mod label_resolver;
 mod lifter;
+mod ordering;
+mod report;
+mod syscalls;
+mod traps;
 
+pub use call_graph::*;
+pub use calls::*;
+pub use cfg::*;
+pub use elf_lifter::*;
+pub use function_discovery::*;
+pub use jump_table::*;
 pub use label_resolver::*;
 pub use lifter::*;
+pub use ordering::*;
+pub use report::*;
+pub use syscalls::*;
+pub use traps::*;
@@ -0,0 +1,159 @@
+use tnj::air::instructions::BasicBlock;
+use yaxpeax_arm::armv8::a64::Opcode;
+
+/// Ordering constraint of a lifted memory access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryOrdering {
+    /// No ordering beyond single-copy atomicity
+    Relaxed,
+    /// Later accesses are not reordered before this one
+    Acquire,
+    /// Acquire that may be reordered before an earlier release to another address (RCpc)
+    AcquirePc,
+    /// Earlier accesses are not reordered after this one
+    Release,
+    /// Both acquire and release
+    AcquireRelease,
+}
+
+impl MemoryOrdering {
+    /// Ordering of an LSE instruction from the acquire (bit 1) and release (bit 0) bits
+    /// yaxpeax decodes into the opcode
+    pub(crate) fn from_ar_bits(ar: u8) -> Self {
+        match ar & 0b11 {
+            0b00 => MemoryOrdering::Relaxed,
+            0b01 => MemoryOrdering::Release,
+            0b10 => MemoryOrdering::Acquire,
+            _ => MemoryOrdering::AcquireRelease,
+        }
+    }
+
+    /// Ordering of a load-acquire or store-release, `None` for plain accesses
+    pub(crate) fn of_access(opcode: Opcode) -> Option<Self> {
+        match opcode {
+            Opcode::LDAR
+            | Opcode::LDARB
+            | Opcode::LDARH
+            | Opcode::LDAXR
+            | Opcode::LDAXRB
+            | Opcode::LDAXRH
+            | Opcode::LDAXP => Some(MemoryOrdering::Acquire),
+            Opcode::LDAPR | Opcode::LDAPRB | Opcode::LDAPRH => Some(MemoryOrdering::AcquirePc),
+            Opcode::STLR
+            | Opcode::STLRB
+            | Opcode::STLRH
+            | Opcode::STLUR
+            | Opcode::STLURB
+            | Opcode::STLURH
+            | Opcode::STLXR
+            | Opcode::STLXRB
+            | Opcode::STLXRH
+            | Opcode::STLXP => Some(MemoryOrdering::Release),
+            _ => None,
+        }
+    }
+
+    /// Whether earlier accesses are ordered before this one
+    pub fn releases(self) -> bool {
+        matches!(
+            self,
+            MemoryOrdering::Release | MemoryOrdering::AcquireRelease
+        )
+    }
+}
+
+/// Accesses ordered by a `DMB` or `DSB`, from the low bits of its option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierAccesses {
+    /// Loads before the barrier against all later accesses (`LD` options)
+    Loads,
+    /// Stores before the barrier against later stores (`ST` options)
+    Stores,
+    /// All accesses
+    All,
+}
+
+impl BarrierAccesses {
+    fn from_option(option: u8) -> Self {
+        match option & 0b11 {
+            0b01 => BarrierAccesses::Loads,
+            0b10 => BarrierAccesses::Stores,
+            _ => BarrierAccesses::All,
+        }
+    }
+}
+
+/// Barrier instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fence {
+    /// Data memory barrier
+    Dmb(BarrierAccesses),
+    /// Data synchronization barrier, which also waits for the accesses to complete
+    Dsb(BarrierAccesses),
+    /// Instruction synchronization barrier
+    Isb,
+}
+
+impl Fence {
+    /// Fence of a barrier instruction, `None` for other instructions
+    pub(crate) fn of_barrier(opcode: Opcode) -> Option<Self> {
+        match opcode {
+            Opcode::DMB(option) => Some(Fence::Dmb(BarrierAccesses::from_option(option))),
+            Opcode::DSB(option) => Some(Fence::Dsb(BarrierAccesses::from_option(option))),
+            Opcode::ISB => Some(Fence::Isb),
+            _ => None,
+        }
+    }
+}
+
+/// What a marker orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderingKind {
+    /// Atomic or ordered memory access
+    Access(MemoryOrdering),
+    /// Barrier between the accesses before and after it
+    Fence(Fence),
+}
+
+/// Ordering marker of a lifted instruction, anchored at the block its AIR was emitted into.
+/// Markers of barriers and releasing accesses are recorded before the AIR of their instruction,
+/// and those of all other accesses after it.
+#[derive(Debug, Clone)]
+pub struct OrderingMarker {
+    /// Address of the instruction
+    pub pc: u64,
+    /// Block containing the AIR of the instruction
+    pub block: BasicBlock,
+    /// Ordering of the access or the barrier
+    pub kind: OrderingKind,
+}
+
+/// Ordering markers of all atomic and ordered memory accesses and barriers of a lifted code
+/// region
+#[derive(Debug, Clone, Default)]
+pub struct MemoryOrderings {
+    markers: Vec<OrderingMarker>,
+}
+
+impl MemoryOrderings {
+    pub(crate) fn push(&mut self, marker: OrderingMarker) {
+        self.markers.push(marker);
+    }
+
+    /// All markers in lifting order
+    pub fn markers(&self) -> &[OrderingMarker] {
+        &self.markers
+    }
+
+    /// Barriers in lifting order
+    pub fn fences(&self) -> impl Iterator<Item = &OrderingMarker> {
+        self.markers
+            .iter()
+            .filter(|marker| matches!(marker.kind, OrderingKind::Fence(_)))
+    }
+
+    /// Marker of the instruction at `pc`, if it is ordered
+    pub fn at(&self, pc: u64) -> Option<&OrderingMarker> {
+        self.markers.iter().find(|marker| marker.pc == pc)
+    }
+}
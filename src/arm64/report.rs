@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use yaxpeax_arm::armv8::a64::Opcode;
+
+/// Why an instruction is listed in a [`LiftReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReportKind {
+    /// Lifted to an opaque value by the fallback
+    Opaque,
+    /// Vector instruction that was skipped
+    SkippedSimd,
+    /// System instruction that was ignored
+    IgnoredSystem,
+}
+
+impl Display for ReportKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::Opaque => write!(f, "opaque"),
+            ReportKind::SkippedSimd => write!(f, "skipped simd"),
+            ReportKind::IgnoredSystem => write!(f, "ignored system"),
+        }
+    }
+}
+
+/// An instruction that was not lifted with full semantics
+#[derive(Debug, Clone)]
+pub struct ReportEntry {
+    /// Address of the instruction
+    pub pc: u64,
+    /// Why the instruction is reported
+    pub kind: ReportKind,
+    /// Decoded opcode
+    pub opcode: Opcode,
+    /// Disassembled instruction
+    pub text: String,
+}
+
+impl ReportEntry {
+    /// Mnemonic of the disassembled instruction
+    pub fn mnemonic(&self) -> &str {
+        self.text.split_whitespace().next().unwrap_or_default()
+    }
+}
+
+/// Instructions that were lifted to opaque values, skipped or ignored
+#[derive(Debug, Clone, Default)]
+pub struct LiftReport {
+    entries: Vec<ReportEntry>,
+}
+
+impl LiftReport {
+    pub(crate) fn push(&mut self, entry: ReportEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Append all entries of another report, e.g. of another function of the same binary
+    pub fn merge(&mut self, other: LiftReport) {
+        self.entries.extend(other.entries);
+    }
+
+    /// All reported instructions in lifting order
+    pub fn entries(&self) -> &[ReportEntry] {
+        &self.entries
+    }
+
+    /// Reported instructions of a single kind
+    pub fn entries_of(&self, kind: ReportKind) -> impl Iterator<Item = &ReportEntry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+
+    /// Addresses per mnemonic for a single kind, most frequent mnemonic first
+    pub fn by_mnemonic(&self, kind: ReportKind) -> Vec<(&str, Vec<u64>)> {
+        let mut mnemonics: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+        for entry in self.entries_of(kind) {
+            mnemonics
+                .entry(entry.mnemonic())
+                .or_default()
+                .push(entry.pc);
+        }
+
+        let mut mnemonics: Vec<_> = mnemonics.into_iter().collect();
+        mnemonics.sort_by(|(_, lhs), (_, rhs)| rhs.len().cmp(&lhs.len()));
+        mnemonics
+    }
+
+    /// Number of occurrences per mnemonic for a single kind, most frequent mnemonic first
+    pub fn counts(&self, kind: ReportKind) -> Vec<(&str, usize)> {
+        self.by_mnemonic(kind)
+            .into_iter()
+            .map(|(mnemonic, pcs)| (mnemonic, pcs.len()))
+            .collect()
+    }
+
+    /// Whether every instruction was lifted with full semantics
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Display for LiftReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for kind in [
+            ReportKind::Opaque,
+            ReportKind::SkippedSimd,
+            ReportKind::IgnoredSystem,
+        ] {
+            let mnemonics = self.by_mnemonic(kind);
+            if mnemonics.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{kind}:")?;
+            for (mnemonic, pcs) in mnemonics {
+                let pcs: Vec<_> = pcs.iter().map(|pc| format!("{pc:#x}")).collect();
+                writeln!(f, "  {mnemonic}: {} ({})", pcs.len(), pcs.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
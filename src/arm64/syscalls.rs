@@ -0,0 +1,126 @@
+use tnj::air::instructions::{BasicBlock, Value};
+
+/// Names system calls by their number in `x8`
+pub trait SyscallTable {
+    /// Name of system call `number`, if the table knows it
+    fn name(&self, number: u64) -> Option<&str>;
+}
+
+/// System call numbers of Linux on AArch64, from the generic `asm-generic/unistd.h` table
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinuxAarch64Syscalls;
+
+const LINUX_AARCH64_SYSCALLS: &[(u64, &str)] = &[
+    (17, "getcwd"),
+    (23, "dup"),
+    (24, "dup3"),
+    (25, "fcntl"),
+    (29, "ioctl"),
+    (34, "mkdirat"),
+    (35, "unlinkat"),
+    (38, "renameat"),
+    (46, "ftruncate"),
+    (48, "faccessat"),
+    (49, "chdir"),
+    (56, "openat"),
+    (57, "close"),
+    (59, "pipe2"),
+    (61, "getdents64"),
+    (62, "lseek"),
+    (63, "read"),
+    (64, "write"),
+    (65, "readv"),
+    (66, "writev"),
+    (67, "pread64"),
+    (68, "pwrite64"),
+    (71, "sendfile"),
+    (72, "pselect6"),
+    (73, "ppoll"),
+    (78, "readlinkat"),
+    (79, "newfstatat"),
+    (80, "fstat"),
+    (82, "fsync"),
+    (93, "exit"),
+    (94, "exit_group"),
+    (96, "set_tid_address"),
+    (98, "futex"),
+    (99, "set_robust_list"),
+    (101, "nanosleep"),
+    (113, "clock_gettime"),
+    (115, "clock_nanosleep"),
+    (124, "sched_yield"),
+    (129, "kill"),
+    (130, "tkill"),
+    (131, "tgkill"),
+    (132, "sigaltstack"),
+    (134, "rt_sigaction"),
+    (135, "rt_sigprocmask"),
+    (139, "rt_sigreturn"),
+    (160, "uname"),
+    (163, "getrlimit"),
+    (165, "getrusage"),
+    (167, "prctl"),
+    (169, "gettimeofday"),
+    (172, "getpid"),
+    (173, "getppid"),
+    (174, "getuid"),
+    (175, "geteuid"),
+    (176, "getgid"),
+    (177, "getegid"),
+    (178, "gettid"),
+    (198, "socket"),
+    (200, "bind"),
+    (201, "listen"),
+    (202, "accept"),
+    (203, "connect"),
+    (206, "sendto"),
+    (207, "recvfrom"),
+    (208, "setsockopt"),
+    (209, "getsockopt"),
+    (210, "shutdown"),
+    (211, "sendmsg"),
+    (212, "recvmsg"),
+    (214, "brk"),
+    (215, "munmap"),
+    (216, "mremap"),
+    (220, "clone"),
+    (221, "execve"),
+    (222, "mmap"),
+    (226, "mprotect"),
+    (227, "msync"),
+    (233, "madvise"),
+    (242, "accept4"),
+    (260, "wait4"),
+    (261, "prlimit64"),
+    (278, "getrandom"),
+    (279, "memfd_create"),
+    (281, "execveat"),
+    (291, "statx"),
+    (435, "clone3"),
+];
+
+impl SyscallTable for LinuxAarch64Syscalls {
+    fn name(&self, number: u64) -> Option<&str> {
+        LINUX_AARCH64_SYSCALLS
+            .binary_search_by_key(&number, |&(n, _)| n)
+            .ok()
+            .map(|index| LINUX_AARCH64_SYSCALLS[index].1)
+    }
+}
+
+/// A system call lifted in [`crate::arm64::SyscallMode::Model`]
+#[derive(Debug, Clone)]
+pub struct Syscall {
+    /// Address of the `SVC`
+    pub pc: u64,
+    /// Block containing the AIR of the system call
+    pub block: BasicBlock,
+    /// Immediate of the `SVC`, which Linux ignores
+    pub imm: u64,
+    /// Number in `x8` if it is a constant set in the same block
+    pub number: Option<u64>,
+    /// Values of `x8` and the argument registers `x0` to `x5` the kernel observes
+    pub inputs: [Value; 7],
+    /// Name of the system call in the syscall table
+    pub name: Option<String>,
+}
@@ -0,0 +1,50 @@
+use tnj::air::instructions::BasicBlock;
+use yaxpeax_arm::armv8::a64::Opcode;
+
+/// Immediate of the `UDF` that Cranelift emits for Wasm trap stubs; the trap reason is kept in a
+/// separate trap table keyed by the address of the stub
+pub const CRANELIFT_TRAP_CODE: u16 = 0xc11f;
+
+/// Instruction that raised a trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapKind {
+    /// `BRK`, a software breakpoint
+    Breakpoint,
+    /// `HLT`, a halt for an external debugger
+    Halt,
+    /// `UDF`, a permanently undefined instruction
+    Undefined,
+}
+
+impl TrapKind {
+    /// Trap raised by `opcode`, `None` if it does not trap unconditionally
+    pub(crate) fn of_opcode(opcode: Opcode) -> Option<Self> {
+        match opcode {
+            Opcode::BRK => Some(TrapKind::Breakpoint),
+            Opcode::HLT => Some(TrapKind::Halt),
+            Opcode::UDF => Some(TrapKind::Undefined),
+            _ => None,
+        }
+    }
+}
+
+/// Unconditional trap of a lifted code region
+#[derive(Debug, Clone)]
+pub struct TrapSite {
+    /// Address of the trapping instruction
+    pub pc: u64,
+    /// Block ending in the trap
+    pub block: BasicBlock,
+    /// Instruction that raised the trap
+    pub kind: TrapKind,
+    /// Immediate of the instruction
+    pub code: u16,
+}
+
+impl TrapSite {
+    /// Whether this is a trap stub emitted by Cranelift, such as the out-of-line stubs of Wasm
+    /// bounds and stack checks
+    pub fn is_cranelift_stub(&self) -> bool {
+        self.kind == TrapKind::Undefined && self.code == CRANELIFT_TRAP_CODE
+    }
+}
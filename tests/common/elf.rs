@@ -0,0 +1,82 @@
+/// Build a minimal little-endian AArch64 ELF64 executable with a `.text` section at `text_addr`
+/// and one `STT_FUNC` symbol per `(name, address, size)` entry.
+pub fn build_elf(text: &[u8], text_addr: u64, functions: &[(&str, u64, u64)]) -> Vec<u8> {
+    const EHDR_SIZE: usize = 64;
+    const SHDR_SIZE: usize = 64;
+    const SYM_SIZE: usize = 24;
+
+    // string tables
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; SYM_SIZE];
+    for &(name, address, size) in functions {
+        let name_offset = strtab.len() as u32;
+        strtab.extend_from_slice(name.as_bytes());
+        strtab.push(0);
+
+        symtab.extend_from_slice(&name_offset.to_le_bytes());
+        symtab.push(0x12); // STB_GLOBAL, STT_FUNC
+        symtab.push(0);
+        symtab.extend_from_slice(&1u16.to_le_bytes()); // .text
+        symtab.extend_from_slice(&address.to_le_bytes());
+        symtab.extend_from_slice(&size.to_le_bytes());
+    }
+    let shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0";
+
+    // layout: header, .text, .symtab, .strtab, .shstrtab, section headers
+    let text_offset = EHDR_SIZE;
+    let symtab_offset = align(text_offset + text.len(), 8);
+    let strtab_offset = symtab_offset + symtab.len();
+    let shstrtab_offset = strtab_offset + strtab.len();
+    let shdr_offset = align(shstrtab_offset + shstrtab.len(), 8);
+
+    let mut elf = Vec::new();
+    elf.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]);
+    elf.extend_from_slice(&[0; 8]);
+    elf.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    elf.extend_from_slice(&183u16.to_le_bytes()); // EM_AARCH64
+    elf.extend_from_slice(&1u32.to_le_bytes());
+    elf.extend_from_slice(&text_addr.to_le_bytes());
+    elf.extend_from_slice(&0u64.to_le_bytes());
+    elf.extend_from_slice(&(shdr_offset as u64).to_le_bytes());
+    elf.extend_from_slice(&0u32.to_le_bytes());
+    elf.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+    elf.extend_from_slice(&56u16.to_le_bytes());
+    elf.extend_from_slice(&0u16.to_le_bytes());
+    elf.extend_from_slice(&(SHDR_SIZE as u16).to_le_bytes());
+    elf.extend_from_slice(&5u16.to_le_bytes());
+    elf.extend_from_slice(&4u16.to_le_bytes());
+
+    elf.extend_from_slice(text);
+    elf.resize(symtab_offset, 0);
+    elf.extend_from_slice(&symtab);
+    elf.extend_from_slice(&strtab);
+    elf.extend_from_slice(shstrtab);
+    elf.resize(shdr_offset, 0);
+
+    // (name, type, flags, addr, offset, size, link, entsize)
+    let headers = [
+        (0u32, 0u32, 0u64, 0u64, 0usize, 0usize, 0u32, 0u64),
+        (1, 1, 0x6, text_addr, text_offset, text.len(), 0, 0),
+        (7, 2, 0, 0, symtab_offset, symtab.len(), 3, SYM_SIZE as u64),
+        (15, 3, 0, 0, strtab_offset, strtab.len(), 0, 0),
+        (23, 3, 0, 0, shstrtab_offset, shstrtab.len(), 0, 0),
+    ];
+    for (name, sh_type, flags, addr, offset, size, link, entsize) in headers {
+        elf.extend_from_slice(&name.to_le_bytes());
+        elf.extend_from_slice(&sh_type.to_le_bytes());
+        elf.extend_from_slice(&flags.to_le_bytes());
+        elf.extend_from_slice(&addr.to_le_bytes());
+        elf.extend_from_slice(&(offset as u64).to_le_bytes());
+        elf.extend_from_slice(&(size as u64).to_le_bytes());
+        elf.extend_from_slice(&link.to_le_bytes());
+        elf.extend_from_slice(&0u32.to_le_bytes());
+        elf.extend_from_slice(&1u64.to_le_bytes());
+        elf.extend_from_slice(&entsize.to_le_bytes());
+    }
+
+    elf
+}
+
+fn align(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
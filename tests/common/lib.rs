@@ -20,6 +20,7 @@ pub struct CheckInstructionArgs {
     pub variable_map: SimpleVariableMap,
     pub print_to_std: bool,
     pub debug: bool,
+    pub base_address: u64,
 }
 
 impl CheckInstructionArgs {
@@ -28,6 +29,7 @@ impl CheckInstructionArgs {
             variable_map,
             print_to_std,
             debug,
+            base_address: 0,
         }
     }
 }
@@ -44,7 +46,8 @@ pub fn check_instruction(
     directives: &str,
     args: CheckInstructionArgs,
 ) -> bool {
-    let lifter = AArch64Lifter::new(bytes, proofs.unwrap_or(&[]));
+    let lifter =
+        AArch64Lifter::new(bytes, proofs.unwrap_or(&[])).with_base_address(args.base_address);
     let code_region = lifter.lift().unwrap();
     let result = code_region.display().to_string();
     if args.debug {
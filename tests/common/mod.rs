@@ -1,2 +1,3 @@
+pub mod elf;
 pub mod lib;
 pub mod simple_variable_map;
@@ -2,68 +2,32 @@ use std::fs;
 use std::path::Path;
 use std::time::{Duration, Instant};
 
-use aarch64_air_lifter::arm64::AArch64Lifter;
-use aarch64_air_lifter::Lifter;
-use elf::abi::STT_FUNC;
-use elf::endian::AnyEndian;
-use elf::ElfBytes;
+use aarch64_air_lifter::arm64::{AArch64ElfError, AArch64ElfLifter, LiftReport};
 
 fn read_elf_file(path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
     let file_data = std::fs::read(path)?;
-    let slice = file_data.as_slice();
-    let file = ElfBytes::<AnyEndian>::minimal_parse(slice)?;
+    // stripped binaries are split by discovered function boundaries instead of symbols
+    let elf_lifter = match AArch64ElfLifter::new(&file_data) {
+        Err(AArch64ElfError::MissingSymbolTable) => AArch64ElfLifter::discover(&file_data)?,
+        result => result?,
+    };
 
-    let text_shdr = file
-        .section_header_by_name(".text")?
-        .ok_or("Missing .text section")?;
-
-    let (bytes, _) = file.section_data(&text_shdr)?;
-
-    let (string_table, _) = file.symbol_table()?.ok_or("Missing symbol table")?;
-
-    let section_addr = text_shdr.sh_addr;
     let mut execution_time = Duration::from_millis(0);
-    for s in string_table.iter().filter(|s| s.st_symtype() == STT_FUNC) {
-        // Calculate offset relative to section start
-        let offset: usize = (s
-            .st_value
-            .checked_sub(section_addr)
-            .ok_or("Symbol address before section start")?)
-        .try_into()
-        .map_err(|_| "Offset too large")?;
-
-        let size: usize = s.st_size.try_into().map_err(|_| "Symbol size too large")?;
-
-        // Check 4-byte alignment
-        if offset % 4 != 0 || size % 4 != 0 {
-            println!("Warning: Symbol {:?} is not 4-byte aligned", s);
-            continue;
-        }
-
-        let end = offset.checked_add(size).ok_or("Symbol range overflow")?;
-
-        // Check bounds
-        if end > bytes.len() {
-            println!("Warning: Symbol {:?} extends beyond section bounds", s);
-            continue;
-        }
-
+    let mut report = LiftReport::default();
+    for function in elf_lifter.functions() {
         let start = Instant::now();
-        let lifter = AArch64Lifter::new(&bytes[offset..end], &[]);
-        let panic = std::panic::catch_unwind(|| match lifter.lift() {
-            Ok(_code_region) => Ok(()),
-            Err(e) => {
-                println!("Error lifting {:?}: {}", s, e);
-                Err(e)
+        match elf_lifter.lift_function(function) {
+            Ok((_code_region, function_report)) => {
+                execution_time += start.elapsed();
+                report.merge(function_report);
             }
-        });
-
-        if let Err(e) = panic {
-            println!("Panicked while processing symbol {:?}: {:?}", s, e);
-        } else {
-            execution_time += start.elapsed();
+            Err(e) => println!(
+                "Error lifting {} at {:#x}: {}",
+                function.name, function.address, e
+            ),
         }
     }
+    print!("{report}");
     println!("Execution time: {:?}", execution_time);
     Ok(())
 }
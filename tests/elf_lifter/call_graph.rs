@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+
+use aarch64_air_lifter::arm64::{AArch64ElfLifter, CallKind, CallSite, CallTarget};
+
+use crate::common::elf::build_elf;
+
+const TEXT: [u8; 32] = [
+    0x03, 0x00, 0x00, 0x94, // bl #0xc
+    0x40, 0x00, 0x3f, 0xd6, // blr x2
+    0x04, 0x00, 0x00, 0x14, // b #0x18
+    0x01, 0x00, 0x00, 0xd4, // svc #0
+    0xfc, 0x0f, 0x00, 0x94, // bl #0x4000
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0xfd, 0xff, 0xff, 0x97, // bl #0xc
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+fn elf() -> Vec<u8> {
+    build_elf(
+        &TEXT,
+        0x400000,
+        &[
+            ("main", 0x400000, 12),
+            ("helper", 0x40000c, 12),
+            ("tail", 0x400018, 8),
+        ],
+    )
+}
+
+fn call(caller: u64, pc: u64, kind: CallKind, target: CallTarget) -> CallSite {
+    CallSite {
+        caller,
+        pc,
+        kind,
+        target,
+    }
+}
+
+#[test]
+fn direct_tail_indirect_and_external_calls() {
+    let elf = elf();
+    let graph = AArch64ElfLifter::new(&elf).unwrap().call_graph();
+
+    assert_eq!(
+        graph.calls(),
+        [
+            call(
+                0x400000,
+                0x400000,
+                CallKind::Call,
+                CallTarget::Function(0x40000c)
+            ),
+            call(0x400000, 0x400004, CallKind::Call, CallTarget::Indirect),
+            call(
+                0x400000,
+                0x400008,
+                CallKind::TailCall,
+                CallTarget::Function(0x400018)
+            ),
+            call(
+                0x40000c,
+                0x400010,
+                CallKind::Call,
+                CallTarget::External(0x404000)
+            ),
+            call(
+                0x400018,
+                0x400018,
+                CallKind::Call,
+                CallTarget::Function(0x40000c)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn functions_reaching_an_instruction() {
+    let elf = elf();
+    let graph = AArch64ElfLifter::new(&elf).unwrap().call_graph();
+
+    assert_eq!(
+        graph.reaching(0x40000c),
+        BTreeSet::from([0x400000, 0x40000c, 0x400018])
+    );
+    assert_eq!(
+        graph.reaching(0x40001c),
+        BTreeSet::from([0x400000, 0x400018])
+    );
+    assert!(graph.reaching(0x404000).is_empty());
+}
+
+#[test]
+fn call_graph_as_dot_and_json() {
+    let elf = elf();
+    let graph = AArch64ElfLifter::new(&elf).unwrap().call_graph();
+
+    let mut dot = Vec::new();
+    graph.write_dot(&mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.contains("function_4194304 [label=\"main\\l0x400000\\l\"];"));
+    assert!(
+        dot.contains("function_4194304 -> function_4194328 [label=\"0x400008\", style=dashed];")
+    );
+    assert!(dot.contains("function_4194316 -> external_4210688 [label=\"0x400010\"];"));
+    assert!(dot.contains("function_4194304 -> indirect [label=\"0x400004\"];"));
+
+    let mut json = Vec::new();
+    graph.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains("{\"name\": \"helper\", \"address\": 4194316, \"end\": 4194328},"));
+    assert!(json.contains(
+        "{\"caller\": 4194304, \"pc\": 4194308, \"kind\": \"call\", \"target\": \"indirect\", \
+         \"address\": null},"
+    ));
+    assert!(json.contains(
+        "{\"caller\": 4194328, \"pc\": 4194328, \"kind\": \"call\", \"target\": \"function\", \
+         \"address\": 4194316}\n  ]"
+    ));
+}
+
+#[test]
+fn indirect_tail_calls_and_failed_functions() {
+    let text = [
+        0x00, 0x02, 0x1f, 0xd6, // br x16
+        0xff, 0xff, 0xff, 0xff, // undefined
+        0xc0, 0x03, 0x5f, 0xd6, // ret
+    ];
+    let elf = build_elf(
+        &text,
+        0x400000,
+        &[("dispatch", 0x400000, 4), ("broken", 0x400004, 8)],
+    );
+    let graph = AArch64ElfLifter::new(&elf).unwrap().call_graph();
+
+    assert_eq!(
+        graph.calls(),
+        [call(
+            0x400000,
+            0x400000,
+            CallKind::TailCall,
+            CallTarget::Indirect
+        )]
+    );
+    assert!(graph.function(0x400004).is_some());
+    assert_eq!(graph.failures().len(), 1);
+    assert_eq!(graph.failures()[0].function, 0x400004);
+}
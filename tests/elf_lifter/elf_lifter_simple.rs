@@ -0,0 +1,138 @@
+use aarch64_air_lifter::arm64::{AArch64ElfError, AArch64ElfLifter};
+
+use crate::common::elf::build_elf;
+
+const TEXT: [u8; 16] = [
+    0x20, 0x00, 0x02, 0x8B, // add x0, x1, x2
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+    0x41, 0x00, 0x03, 0x8B, // add x1, x2, x3
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+];
+
+#[test]
+fn split_by_symbols() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[("second", 0x400008, 8), ("first", 0x400000, 8)],
+    );
+
+    let lifter = AArch64ElfLifter::new(&elf).unwrap();
+    let functions: Vec<_> = lifter
+        .functions()
+        .iter()
+        .map(|f| (f.name.as_str(), f.address, f.code))
+        .collect();
+
+    assert_eq!(
+        functions,
+        vec![
+            ("first", 0x400000, &TEXT[..8]),
+            ("second", 0x400008, &TEXT[8..]),
+        ]
+    );
+}
+
+#[test]
+fn unsized_symbol_extends_to_next_function() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[("first", 0x400000, 0), ("second", 0x400008, 0)],
+    );
+
+    let lifter = AArch64ElfLifter::new(&elf).unwrap();
+    let sizes: Vec<_> = lifter.functions().iter().map(|f| f.code.len()).collect();
+
+    assert_eq!(sizes, vec![8, 8]);
+}
+
+#[test]
+fn lift_all_functions() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[("first", 0x400000, 8), ("second", 0x400008, 8)],
+    );
+
+    let lifted = AArch64ElfLifter::new(&elf).unwrap().lift().unwrap();
+    let names: Vec<_> = lifted.iter().map(|f| f.name.as_str()).collect();
+
+    assert_eq!(names, vec!["first", "second"]);
+}
+
+#[test]
+fn unaligned_symbols_are_skipped() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[
+            ("first", 0x400000, 0),
+            ("odd", 0x400006, 2),
+            ("second", 0x400008, 8),
+        ],
+    );
+
+    let lifter = AArch64ElfLifter::new(&elf).unwrap();
+    let functions: Vec<_> = lifter
+        .functions()
+        .iter()
+        .map(|f| (f.name.as_str(), f.code.len()))
+        .collect();
+
+    assert_eq!(functions, vec![("first", 8), ("second", 8)]);
+    assert!(matches!(
+        lifter.skipped(),
+        [AArch64ElfError::Unaligned(name)] if name == "odd"
+    ));
+}
+
+#[test]
+fn unaligned_end_is_truncated() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[("first", 0x400000, 6), ("second", 0x400008, 8)],
+    );
+
+    let lifter = AArch64ElfLifter::new(&elf).unwrap();
+    let functions: Vec<_> = lifter
+        .functions()
+        .iter()
+        .map(|f| (f.name.as_str(), f.code))
+        .collect();
+
+    assert_eq!(
+        functions,
+        vec![("first", &TEXT[..4]), ("second", &TEXT[8..])]
+    );
+    assert!(lifter.skipped().is_empty());
+}
+
+#[test]
+fn aliases_share_a_function() {
+    let elf = build_elf(
+        &TEXT,
+        0x400000,
+        &[
+            ("first", 0x400000, 8),
+            ("first_alias", 0x400000, 0),
+            ("second", 0x400008, 8),
+        ],
+    );
+
+    let lifter = AArch64ElfLifter::new(&elf).unwrap();
+    let functions: Vec<_> = lifter
+        .functions()
+        .iter()
+        .map(|f| (f.name.as_str(), f.aliases.clone(), f.code.len()))
+        .collect();
+
+    assert_eq!(
+        functions,
+        vec![
+            ("first", vec!["first_alias".to_string()], 8),
+            ("second", vec![], 8),
+        ]
+    );
+}
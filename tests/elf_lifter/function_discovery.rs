@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use aarch64_air_lifter::arm64::{AArch64ElfLifter, FunctionDiscovery, FunctionSource};
+
+use crate::common::elf::build_elf;
+
+const TEXT: [u8; 68] = [
+    0x5f, 0x24, 0x03, 0xd5, // bti c
+    0x3f, 0x23, 0x03, 0xd5, // paciasp
+    0xfd, 0x7b, 0xbf, 0xa9, // stp x29, x30, [sp, #-16]!
+    0x08, 0x00, 0x00, 0x94, // bl #0x2c
+    0xfd, 0x7b, 0xc1, 0xa8, // ldp x29, x30, [sp], #16
+    0xbf, 0x23, 0x03, 0xd5, // autiasp
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0xfd, 0x7b, 0xbe, 0xa9, // stp x29, x30, [sp, #-32]!
+    0xfd, 0x03, 0x00, 0x91, // mov x29, sp
+    0xfd, 0x7b, 0xc2, 0xa8, // ldp x29, x30, [sp], #32
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x00, 0x04, 0x00, 0x91, // add x0, x0, #1
+    0xfd, 0x7b, 0xbf, 0xa9, // stp x29, x30, [sp, #-16]!
+    0xfd, 0x7b, 0xc1, 0xa8, // ldp x29, x30, [sp], #16
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x00, 0x00, 0x80, 0xd2, // mov x0, #0
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+/// `.eh_frame` at 0x2000 with FDEs for 0x101c..0x1024 and 0x103c..0x1044
+const EH_FRAME: [u8; 64] = [
+    0x10, 0x00, 0x00, 0x00, // CIE length
+    0x00, 0x00, 0x00, 0x00, // CIE id
+    0x01, b'z', b'R', 0x00, // version 1, augmentation "zR"
+    0x01, 0x78, 0x1e, 0x01, // code alignment 1, data alignment -8, x30, augmentation length 1
+    0x1b, 0x00, 0x00, 0x00, // pcrel sdata4 pointers, padding
+    0x10, 0x00, 0x00, 0x00, // FDE length
+    0x18, 0x00, 0x00, 0x00, // CIE pointer
+    0x00, 0xf0, 0xff, 0xff, // pc_begin 0x101c - 0x201c
+    0x08, 0x00, 0x00, 0x00, // pc_range
+    0x00, 0x00, 0x00, 0x00, // augmentation length 0, padding
+    0x10, 0x00, 0x00, 0x00, // FDE length
+    0x2c, 0x00, 0x00, 0x00, // CIE pointer
+    0x0c, 0xf0, 0xff, 0xff, // pc_begin 0x103c - 0x2030
+    0x08, 0x00, 0x00, 0x00, // pc_range
+    0x00, 0x00, 0x00, 0x00, // augmentation length 0, padding
+    0x00, 0x00, 0x00, 0x00, // terminator
+];
+
+#[test]
+fn functions_from_calls_prologues_and_frames() {
+    use FunctionSource::*;
+
+    let functions = FunctionDiscovery::new(&TEXT, 0x1000)
+        .with_entry_point(0x1000)
+        .with_eh_frame(0x2000, &EH_FRAME)
+        .discover();
+    let ranges: Vec<_> = functions
+        .iter()
+        .map(|f| (f.address, f.end(), f.sources.clone()))
+        .collect();
+
+    assert_eq!(
+        ranges,
+        vec![
+            (0x1000, 0x101c, BTreeSet::from([EntryPoint, Prologue])),
+            (0x101c, 0x1024, BTreeSet::from([FrameDescription, Prologue])),
+            (0x102c, 0x103c, BTreeSet::from([CallTarget])),
+            (0x103c, 0x1044, BTreeSet::from([FrameDescription])),
+        ]
+    );
+    assert_eq!(functions[1].code, &TEXT[0x1c..0x24]);
+}
+
+#[test]
+fn stripped_elf_is_split_by_discovered_functions() {
+    let elf = build_elf(&TEXT, 0x400000, &[]);
+
+    let lifter = AArch64ElfLifter::discover(&elf).unwrap();
+    let functions: Vec<_> = lifter
+        .functions()
+        .iter()
+        .map(|f| (f.name.as_str(), f.code.len()))
+        .collect();
+
+    assert_eq!(
+        functions,
+        vec![
+            ("sub_400000", 0x1c),
+            ("sub_40001c", 0x10),
+            ("sub_40002c", 0x18)
+        ]
+    );
+    assert!(lifter.lift().is_ok());
+}
@@ -0,0 +1,3 @@
+pub mod elf_lifter_simple;
+pub mod function_discovery;
+pub mod call_graph;
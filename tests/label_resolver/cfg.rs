@@ -0,0 +1,86 @@
+use aarch64_air_lifter::arm64::{CallMode, CfgEdge, DataRegion, EdgeKind, LabelResolver};
+use yaxpeax_arch::Arch;
+use yaxpeax_arm::armv8::a64::ARMv8;
+
+use super::jump_tables::{SWITCH, TABLE};
+
+const BRANCHES: [u8; 20] = [
+    0x60, 0x00, 0x00, 0xb4, // cbz x0, #0xc
+    0x3f, 0x00, 0x00, 0x94, // bl #0x100
+    0x02, 0x00, 0x00, 0x14, // b #0x10
+    0x41, 0x00, 0x03, 0x8b, // add x1, x2, x3
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+fn edge(from: u64, to: Option<u64>, kind: EdgeKind) -> CfgEdge {
+    CfgEdge { from, to, kind }
+}
+
+#[test]
+fn edges_are_classified() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let resolver = LabelResolver::with_call_mode(&BRANCHES, 0, CallMode::Call, &decoder).unwrap();
+    let cfg = resolver.control_flow_graph();
+
+    let starts: Vec<(u64, u64)> = cfg.nodes().map(|node| (node.start, node.end)).collect();
+    assert_eq!(
+        starts,
+        [(0, 4), (4, 8), (8, 0xc), (0xc, 0x10), (0x10, 0x14)]
+    );
+
+    let edges: Vec<CfgEdge> = cfg.edges().copied().collect();
+    assert_eq!(
+        edges,
+        [
+            edge(0, Some(0xc), EdgeKind::Conditional),
+            edge(0, Some(4), EdgeKind::Fallthrough),
+            edge(4, Some(0x100), EdgeKind::Call),
+            edge(4, Some(8), EdgeKind::Fallthrough),
+            edge(8, Some(0x10), EdgeKind::Branch),
+            edge(0xc, Some(0x10), EdgeKind::Fallthrough),
+            edge(0x10, None, EdgeKind::Return),
+        ]
+    );
+
+    let ret = cfg.node(0x10).unwrap();
+    assert_eq!(
+        ret.predecessors,
+        [
+            edge(8, Some(0x10), EdgeKind::Branch),
+            edge(0xc, Some(0x10), EdgeKind::Fallthrough),
+        ]
+    );
+    assert_eq!(cfg.node_containing(0x12).unwrap().start, 0x10);
+    assert!(cfg.node_containing(0x14).is_none());
+}
+
+#[test]
+fn calls_do_not_return_in_jump_mode() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let resolver = LabelResolver::with_call_mode(&BRANCHES, 0, CallMode::Jump, &decoder).unwrap();
+    let cfg = resolver.control_flow_graph();
+
+    let call = cfg.node(4).unwrap();
+    assert_eq!(call.successors, [edge(4, Some(0x100), EdgeKind::Call)]);
+    assert!(cfg.node(8).unwrap().predecessors.is_empty());
+}
+
+#[test]
+fn jump_table_targets_are_dynamic_edges() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let data = [DataRegion::new(0x40, &TABLE)];
+    let resolver =
+        LabelResolver::with_data_regions(&SWITCH, 0, CallMode::Jump, &data, &decoder).unwrap();
+    let cfg = resolver.control_flow_graph();
+
+    let switch = cfg.node_containing(0x1c).unwrap();
+    assert_eq!(
+        switch.successors,
+        [
+            edge(switch.start, Some(0x20), EdgeKind::Dynamic),
+            edge(switch.start, Some(0x28), EdgeKind::Dynamic),
+            edge(switch.start, Some(0x30), EdgeKind::Dynamic),
+        ]
+    );
+    assert_eq!(cfg.node(0x28).unwrap().predecessors.len(), 1);
+}
@@ -0,0 +1,82 @@
+use aarch64_air_lifter::arm64::{CallMode, DataRegion, LabelResolver};
+use yaxpeax_arch::Arch;
+use yaxpeax_arm::armv8::a64::ARMv8;
+
+pub const SWITCH: [u8; 64] = [
+    0x1f, 0x0c, 0x00, 0x71, // cmp w0, #3
+    0xa8, 0x01, 0x00, 0x54, // b.hi #0x38
+    0xe8, 0x03, 0x00, 0x2a, // mov w8, w0
+    0xa9, 0x01, 0x00, 0x10, // adr x9, #0x40
+    0x2a, 0x69, 0x68, 0x38, // ldrb w10, [x9, x8]
+    0x6b, 0x00, 0x00, 0x10, // adr x11, #0x20
+    0x6b, 0x09, 0x0a, 0x8b, // add x11, x11, x10, lsl #2
+    0x60, 0x01, 0x1f, 0xd6, // br x11
+    0x40, 0x01, 0x80, 0x52, // mov w0, #10
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x60, 0x01, 0x80, 0x52, // mov w0, #11
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x80, 0x01, 0x80, 0x52, // mov w0, #12
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x00, 0x00, 0x80, 0x52, // mov w0, #0
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+/// Byte offsets in units of instructions from the first case at 0x20
+pub const TABLE: [u8; 4] = [0, 2, 4, 2];
+
+#[test]
+fn jump_table_targets_are_recovered() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let data = [DataRegion::new(0x40, &TABLE)];
+    let resolver =
+        LabelResolver::with_data_regions(&SWITCH, 0, CallMode::Jump, &data, &decoder).unwrap();
+
+    let table = resolver.jump_table(0x1c).unwrap();
+    assert_eq!(table.table, 0x40);
+    assert_eq!(table.targets, [0x20, 0x28, 0x30, 0x28]);
+    for target in [0x20, 0x28, 0x30] {
+        assert!(resolver.is_branch_target(target));
+    }
+}
+
+#[test]
+fn unreadable_table_is_not_recovered() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let resolver = LabelResolver::new(&SWITCH, &decoder).unwrap();
+
+    assert!(resolver.jump_table(0x1c).is_none());
+    assert!(!resolver.is_branch_target(0x28));
+}
+
+/// Switch as emitted by clang, with a table of word offsets relative to the table
+pub const ADRP_SWITCH: [u8; 56] = [
+    0x1f, 0x08, 0x00, 0x71, // cmp w0, #2
+    0x62, 0x01, 0x00, 0x54, // b.hs #0x30
+    0xe9, 0x03, 0x00, 0x2a, // mov w9, w0
+    0x08, 0x00, 0x00, 0xb0, // adrp x8, #0x1000
+    0x08, 0x41, 0x00, 0x91, // add x8, x8, #16
+    0x09, 0x79, 0xa9, 0xb8, // ldrsw x9, [x8, x9, lsl #2]
+    0x08, 0x01, 0x09, 0x8b, // add x8, x8, x9
+    0x00, 0x01, 0x1f, 0xd6, // br x8
+    0x40, 0x01, 0x80, 0x52, // mov w0, #10
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x60, 0x01, 0x80, 0x52, // mov w0, #11
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+    0x00, 0x00, 0x80, 0x52, // mov w0, #0
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+/// Offsets of 0x20 and 0x28 from the table at 0x1010
+pub const ADRP_TABLE: [u8; 8] = [0x10, 0xf0, 0xff, 0xff, 0x18, 0xf0, 0xff, 0xff];
+
+#[test]
+fn adrp_table_with_signed_entries_is_recovered() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let data = [DataRegion::new(0x1010, &ADRP_TABLE)];
+    let resolver =
+        LabelResolver::with_data_regions(&ADRP_SWITCH, 0, CallMode::Jump, &data, &decoder).unwrap();
+
+    let table = resolver.jump_table(0x1c).unwrap();
+    assert_eq!(table.table, 0x1010);
+    assert_eq!(table.targets, [0x20, 0x28]);
+}
@@ -1 +1,3 @@
 pub mod label_resolver_simple;
+pub mod jump_tables;
+pub mod cfg;
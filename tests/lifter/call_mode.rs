@@ -0,0 +1,84 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, CallMode, Callee, ClobberModel};
+use aarch64_air_lifter::Lifter;
+
+const BYTES: [u8; 16] = [
+    0x03, 0x00, 0x00, 0x94, // bl #0xc
+    0x40, 0x00, 0x3F, 0xD6, // blr x2
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+];
+
+#[test]
+fn jump_mode_merges_callee() {
+    let code_region = AArch64Lifter::new(&BYTES, &[]).lift().unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains("jump block_12"));
+    assert!(result.contains("dynamic_jump"));
+}
+
+#[test]
+fn call_mode_continues_at_return_address() {
+    let code_region = AArch64Lifter::new(&BYTES, &[])
+        .with_call_mode(CallMode::Call)
+        .lift()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains("write_reg.i64 0x4, \"x30\""));
+    assert!(result.contains("jump block_4"));
+    assert!(result.contains("write_reg.i64 0x8, \"x30\""));
+    assert!(result.contains("jump block_8"));
+    assert!(!result.contains("block_12"));
+    assert!(!result.contains("\"x18\""));
+}
+
+#[test]
+fn aapcs64_clobbers_caller_saved_registers() {
+    let code_region = AArch64Lifter::new(&BYTES, &[])
+        .with_call_mode(CallMode::Call)
+        .with_clobber_model(ClobberModel::Aapcs64)
+        .lift()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains("\"x0\""));
+    assert!(result.contains("\"x18\""));
+    assert!(result.contains("\"n\""));
+    assert!(!result.contains("\"x19\""));
+}
+
+#[test]
+fn call_mode_records_callees() {
+    let (code_region, calls) = AArch64Lifter::new(&BYTES, &[])
+        .with_call_mode(CallMode::Call)
+        .lift_with_calls()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].pc, 0x0);
+    assert!(matches!(calls[0].callee, Callee::Direct(0xc)));
+    assert_eq!(calls[1].pc, 0x4);
+    assert!(matches!(calls[1].callee, Callee::Indirect(_)));
+    assert!(result.contains("read_reg \"x2\""));
+}
+
+#[test]
+fn call_mode_calls_authenticated_target() {
+    let bytes = [
+        0x43, 0x08, 0x3f, 0xd7, // blraa x2, x3
+        0xc0, 0x03, 0x5f, 0xd6, // ret
+    ];
+    let (code_region, calls) = AArch64Lifter::new(&bytes, &[])
+        .with_call_mode(CallMode::Call)
+        .lift_with_calls()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert_eq!(calls.len(), 1);
+    assert!(matches!(calls[0].callee, Callee::Indirect(_)));
+    assert!(result.contains("trapif"));
+    assert!(result.contains("write_reg.i64 0x4, \"x30\""));
+    assert!(result.contains("jump block_4"));
+}
@@ -0,0 +1,63 @@
+use crate::label_resolver::jump_tables::{SWITCH, TABLE};
+use aarch64_air_lifter::arm64::{AArch64Lifter, CallMode, DataRegion, LabelResolver};
+use yaxpeax_arch::Arch;
+use yaxpeax_arm::armv8::a64::ARMv8;
+
+const BYTES: [u8; 16] = [
+    0x03, 0x00, 0x00, 0x94, // bl #0xc
+    0x40, 0x00, 0x3F, 0xD6, // blr x2
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+    0xC0, 0x03, 0x5F, 0xD6, // ret
+];
+
+fn lifted_dot(lifter: AArch64Lifter) -> String {
+    let mut dot = Vec::new();
+    lifter.write_dot(&mut dot).unwrap();
+    String::from_utf8(dot).unwrap()
+}
+
+#[test]
+fn lifted_control_flow_as_dot() {
+    let dot = lifted_dot(AArch64Lifter::new(&BYTES, &[]).with_call_mode(CallMode::Call));
+
+    assert!(dot.starts_with("digraph cfg {"));
+    assert!(dot.contains("write_reg.i64 0x4, \\\"x30\\\"\\l"));
+    assert!(dot.contains("entry -> block_4 [label=\"jump\"];"));
+    assert!(dot.contains("block_4 -> block_8 [label=\"jump\"];"));
+    assert!(dot.contains("block_8 -> unknown [label=\"dynamic_jump\"];"));
+    assert!(!dot.contains("block_12"));
+}
+
+#[test]
+fn external_blocks_as_dot() {
+    let dot = lifted_dot(AArch64Lifter::new(&BYTES[..4], &[]));
+
+    assert!(dot.contains("external_12 [label=\"external_12:\\l"));
+    assert!(dot.contains(", style=dashed];"));
+    assert!(dot.contains("entry -> external_12 [label=\"jump\"];"));
+    assert!(dot.contains("external_12 -> unknown [label=\"dynamic_jump\"];"));
+}
+
+#[test]
+fn jump_table_cases_as_dot() {
+    let dot = lifted_dot(
+        AArch64Lifter::new(&SWITCH, &[]).with_data_region(DataRegion::new(0x40, &TABLE)),
+    );
+
+    assert!(dot.contains("jump_table_case"));
+    assert!(dot.contains(" -> block_32 [label=\"true\"];"));
+    assert!(dot.contains(" -> block_48 [label=\"jump\"];"));
+}
+
+#[test]
+fn external_targets_as_dot() {
+    let decoder = <ARMv8 as Arch>::Decoder::default();
+    let resolver = LabelResolver::new(&BYTES[..4], &decoder).unwrap();
+    let mut dot = Vec::new();
+    resolver.control_flow_graph().write_dot(&mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+
+    assert!(dot.contains("block_0 [label=\"block_0:\\l0x0000..0x0004\\l\"];"));
+    assert!(dot.contains("external_12 [style=dashed];"));
+    assert!(dot.contains("block_0 -> external_12 [label=\"call\"];"));
+}
@@ -0,0 +1,20 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_atomics_ldaddal() {
+    run_test_from_yaml("tests/lifter/insts/tests/atomics.yaml", "atomics_ldaddal");
+}
+#[test]
+pub fn test_atomics_casal() {
+    run_test_from_yaml("tests/lifter/insts/tests/atomics.yaml", "atomics_casal");
+}
+#[test]
+pub fn test_atomics_swpb() {
+    run_test_from_yaml("tests/lifter/insts/tests/atomics.yaml", "atomics_swpb");
+}
+#[test]
+pub fn test_atomics_stadd() {
+    run_test_from_yaml("tests/lifter/insts/tests/atomics.yaml", "atomics_stadd");
+}
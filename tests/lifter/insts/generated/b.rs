@@ -6,3 +6,7 @@ use crate::lifter::yaml_tests::run_test_from_yaml;
 pub fn test_b_1() {
     run_test_from_yaml("tests/lifter/insts/tests/b.yaml", "b_1");
 }
+#[test]
+pub fn test_b_2() {
+    run_test_from_yaml("tests/lifter/insts/tests/b.yaml", "b_2");
+}
@@ -0,0 +1,16 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_base_address_1() {
+    run_test_from_yaml("tests/lifter/insts/tests/base_address.yaml", "base_address_1");
+}
+#[test]
+pub fn test_base_address_2() {
+    run_test_from_yaml("tests/lifter/insts/tests/base_address.yaml", "base_address_2");
+}
+#[test]
+pub fn test_base_address_3() {
+    run_test_from_yaml("tests/lifter/insts/tests/base_address.yaml", "base_address_3");
+}
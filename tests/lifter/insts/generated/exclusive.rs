@@ -0,0 +1,20 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_exclusive_pair() {
+    run_test_from_yaml("tests/lifter/insts/tests/exclusive.yaml", "exclusive_pair");
+}
+#[test]
+pub fn test_exclusive_store_without_reservation() {
+    run_test_from_yaml("tests/lifter/insts/tests/exclusive.yaml", "exclusive_store_without_reservation");
+}
+#[test]
+pub fn test_exclusive_clrex() {
+    run_test_from_yaml("tests/lifter/insts/tests/exclusive.yaml", "exclusive_clrex");
+}
+#[test]
+pub fn test_exclusive_pair_across_blocks() {
+    run_test_from_yaml("tests/lifter/insts/tests/exclusive.yaml", "exclusive_pair_across_blocks");
+}
@@ -0,0 +1,36 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_fp_fadd() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fadd");
+}
+#[test]
+pub fn test_fp_fmov_imm() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fmov_imm");
+}
+#[test]
+pub fn test_fp_fcmp_zero() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fcmp_zero");
+}
+#[test]
+pub fn test_fp_fcsel() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fcsel");
+}
+#[test]
+pub fn test_fp_scvtf() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_scvtf");
+}
+#[test]
+pub fn test_fp_fadd_half() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fadd_half");
+}
+#[test]
+pub fn test_fp_fcvt_from_half() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fcvt_from_half");
+}
+#[test]
+pub fn test_fp_fcvt_to_half() {
+    run_test_from_yaml("tests/lifter/insts/tests/fp.yaml", "fp_fcvt_to_half");
+}
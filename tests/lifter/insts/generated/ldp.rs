@@ -10,3 +10,7 @@ pub fn test_ldp_1() {
 pub fn test_ldp_2() {
     run_test_from_yaml("tests/lifter/insts/tests/ldp.yaml", "ldp_2");
 }
+#[test]
+pub fn test_ldp_3() {
+    run_test_from_yaml("tests/lifter/insts/tests/ldp.yaml", "ldp_3");
+}
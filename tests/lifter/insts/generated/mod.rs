@@ -80,3 +80,12 @@ pub mod orr;
 pub mod sbcs;
 pub mod cbz;
 pub mod ldr;
+pub mod base_address;
+pub mod fp;
+pub mod neon;
+pub mod simd_mem;
+pub mod atomics;
+pub mod exclusive;
+pub mod sysregs;
+pub mod pac;
+pub mod traps;
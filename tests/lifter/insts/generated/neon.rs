@@ -0,0 +1,76 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_neon_dup() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_dup");
+}
+#[test]
+pub fn test_neon_cmeq() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_cmeq");
+}
+#[test]
+pub fn test_neon_ext() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_ext");
+}
+#[test]
+pub fn test_neon_zip1() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_zip1");
+}
+#[test]
+pub fn test_neon_zip2() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_zip2");
+}
+#[test]
+pub fn test_neon_uzp1() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_uzp1");
+}
+#[test]
+pub fn test_neon_uzp2() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_uzp2");
+}
+#[test]
+pub fn test_neon_trn1() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_trn1");
+}
+#[test]
+pub fn test_neon_trn2() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_trn2");
+}
+#[test]
+pub fn test_neon_tbl() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_tbl");
+}
+#[test]
+pub fn test_neon_ins() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_ins");
+}
+#[test]
+pub fn test_neon_umov() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_umov");
+}
+#[test]
+pub fn test_neon_smov() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_smov");
+}
+#[test]
+pub fn test_neon_shl() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_shl");
+}
+#[test]
+pub fn test_neon_ushr() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_ushr");
+}
+#[test]
+pub fn test_neon_sshr() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_sshr");
+}
+#[test]
+pub fn test_neon_addv() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_addv");
+}
+#[test]
+pub fn test_neon_umaxv() {
+    run_test_from_yaml("tests/lifter/insts/tests/neon.yaml", "neon_umaxv");
+}
@@ -0,0 +1,28 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_pac_paciasp() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_paciasp");
+}
+#[test]
+pub fn test_pac_autiasp() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_autiasp");
+}
+#[test]
+pub fn test_pac_xpaclri() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_xpaclri");
+}
+#[test]
+pub fn test_pac_retaa() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_retaa");
+}
+#[test]
+pub fn test_pac_blraa() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_blraa");
+}
+#[test]
+pub fn test_pac_bti_landing_pad() {
+    run_test_from_yaml("tests/lifter/insts/tests/pac.yaml", "pac_bti_landing_pad");
+}
@@ -10,3 +10,11 @@ pub fn test_sbfm_1() {
 pub fn test_sbfm_2() {
     run_test_from_yaml("tests/lifter/insts/tests/sbfm.yaml", "sbfm_2");
 }
+#[test]
+pub fn test_sbfm_sxtb() {
+    run_test_from_yaml("tests/lifter/insts/tests/sbfm.yaml", "sbfm_sxtb");
+}
+#[test]
+pub fn test_sbfm_asr() {
+    run_test_from_yaml("tests/lifter/insts/tests/sbfm.yaml", "sbfm_asr");
+}
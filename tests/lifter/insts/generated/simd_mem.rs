@@ -0,0 +1,36 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_simd_mem_ldr_q() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_ldr_q");
+}
+#[test]
+pub fn test_simd_mem_str_d() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_str_d");
+}
+#[test]
+pub fn test_simd_mem_ldp_q() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_ldp_q");
+}
+#[test]
+pub fn test_simd_mem_ld1() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_ld1");
+}
+#[test]
+pub fn test_simd_mem_st1() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_st1");
+}
+#[test]
+pub fn test_simd_mem_ldr_h() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_ldr_h");
+}
+#[test]
+pub fn test_simd_mem_str_b() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_str_b");
+}
+#[test]
+pub fn test_simd_mem_ldr_q_post_index() {
+    run_test_from_yaml("tests/lifter/insts/tests/simd_mem.yaml", "simd_mem_ldr_q_post_index");
+}
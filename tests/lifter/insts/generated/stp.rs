@@ -14,3 +14,7 @@ pub fn test_stp_2() {
 pub fn test_stp_3() {
     run_test_from_yaml("tests/lifter/insts/tests/stp.yaml", "stp_3");
 }
+#[test]
+pub fn test_stp_4() {
+    run_test_from_yaml("tests/lifter/insts/tests/stp.yaml", "stp_4");
+}
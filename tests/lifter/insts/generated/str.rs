@@ -18,3 +18,7 @@ pub fn test_str_3() {
 pub fn test_str_4() {
     run_test_from_yaml("tests/lifter/insts/tests/str.yaml", "str_4");
 }
+#[test]
+pub fn test_str_5() {
+    run_test_from_yaml("tests/lifter/insts/tests/str.yaml", "str_5");
+}
@@ -10,3 +10,7 @@ pub fn test_subs_1() {
 pub fn test_subs_2() {
     run_test_from_yaml("tests/lifter/insts/tests/subs.yaml", "subs_2");
 }
+#[test]
+pub fn test_cmp_imm() {
+    run_test_from_yaml("tests/lifter/insts/tests/subs.yaml", "cmp_imm");
+}
@@ -0,0 +1,12 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_sysregs_mrs_nzcv() {
+    run_test_from_yaml("tests/lifter/insts/tests/sysregs.yaml", "sysregs_mrs_nzcv");
+}
+#[test]
+pub fn test_sysregs_msr_nzcv() {
+    run_test_from_yaml("tests/lifter/insts/tests/sysregs.yaml", "sysregs_msr_nzcv");
+}
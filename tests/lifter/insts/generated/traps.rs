@@ -0,0 +1,16 @@
+#![cfg_attr(rustfmt, rustfmt_skip)]// ⚠️ Automatically generated file, do not edit! ⚠️
+
+use crate::lifter::yaml_tests::run_test_from_yaml;
+
+#[test]
+pub fn test_brk_1() {
+    run_test_from_yaml("tests/lifter/insts/tests/traps.yaml", "brk_1");
+}
+#[test]
+pub fn test_hlt_1() {
+    run_test_from_yaml("tests/lifter/insts/tests/traps.yaml", "hlt_1");
+}
+#[test]
+pub fn test_udf_1() {
+    run_test_from_yaml("tests/lifter/insts/tests/traps.yaml", "udf_1");
+}
@@ -0,0 +1,38 @@
+use crate::label_resolver::jump_tables::{ADRP_SWITCH, ADRP_TABLE, SWITCH, TABLE};
+use aarch64_air_lifter::arm64::{AArch64Lifter, DataRegion};
+use aarch64_air_lifter::Lifter;
+
+#[test]
+fn br_with_jump_table_branches_to_every_case() {
+    let code_region = AArch64Lifter::new(&SWITCH, &[])
+        .with_data_region(DataRegion::new(0x40, &TABLE))
+        .lift()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains(", block_32, jump_table_case"));
+    assert!(result.contains("block_40"));
+    assert!(result.contains("jump block_48"));
+}
+
+#[test]
+fn br_without_jump_table_is_a_dynamic_jump() {
+    let code_region = AArch64Lifter::new(&SWITCH, &[]).lift().unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(!result.contains("jump_table_case"));
+    assert!(result.contains("dynamic_jump"));
+}
+
+#[test]
+fn adrp_switch_branches_to_every_case() {
+    let code_region = AArch64Lifter::new(&ADRP_SWITCH, &[])
+        .with_data_region(DataRegion::new(0x1010, &ADRP_TABLE))
+        .lift()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains(", block_32, jump_table_case"));
+    assert!(result.contains("jump block_40"));
+    assert!(!result.contains("dynamic_jump"));
+}
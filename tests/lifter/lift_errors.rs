@@ -0,0 +1,14 @@
+use aarch64_air_lifter::arm64::AArch64Lifter;
+use aarch64_air_lifter::Lifter;
+
+#[test]
+fn register_pair_destination_is_lifted() {
+    let bytes = [
+        0x82, 0x7C, 0x20, 0x48, // casp x0, x1, x2, x3, [x4]
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]);
+    let (_code_region, warnings) = lifter.lift_with_warnings().unwrap();
+
+    assert!(warnings.is_empty());
+}
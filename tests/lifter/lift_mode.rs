@@ -0,0 +1,103 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, AArch64LifterError, LiftMode};
+use aarch64_air_lifter::Lifter;
+
+const BYTES: [u8; 12] = [
+    0x20, 0x00, 0x02, 0x8B, // add x0, x1, x2
+    0x20, 0x40, 0xC2, 0x1A, // crc32b w0, w1, w2
+    0x20, 0xD4, 0x22, 0x4E, // fadd v0.4s, v1.4s, v2.4s
+];
+
+#[test]
+fn strict_rejects_unsupported_instruction() {
+    let lifter = AArch64Lifter::new(&BYTES, &[]).with_mode(LiftMode::Strict);
+
+    match lifter.lift() {
+        Err(AArch64LifterError::UnsupportedInstruction { pc, text, .. }) => {
+            assert_eq!(pc, 4);
+            assert_eq!(text, "crc32b w0, w1, w2");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("strict lifting succeeded"),
+    }
+}
+
+#[test]
+fn lenient_collects_warnings() {
+    let lifter = AArch64Lifter::new(&BYTES, &[]);
+    let (_code_region, warnings) = lifter.lift_with_warnings().unwrap();
+
+    let pcs: Vec<_> = warnings
+        .iter()
+        .map(|w| match w {
+            AArch64LifterError::UnsupportedInstruction { pc, .. } => *pc,
+            e => panic!("unexpected warning: {e}"),
+        })
+        .collect();
+
+    assert_eq!(pcs, vec![4, 8]);
+}
+
+#[test]
+fn strict_rejects_ignored_system_instruction() {
+    let bytes = [
+        0x1F, 0x20, 0x03, 0xD5, // nop
+        0xFF, 0x2F, 0x03, 0xD5, // hint #0x7f
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+
+    match lifter.lift() {
+        Err(AArch64LifterError::UnsupportedInstruction { pc, .. }) => assert_eq!(pc, 4),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("strict lifting succeeded"),
+    }
+}
+
+#[test]
+fn strict_rejects_half_precision() {
+    let cases: [([u8; 4], &str); 3] = [
+        ([0x20, 0x28, 0xE2, 0x1E], "fadd h0, h1, h2"),
+        ([0x20, 0x40, 0xE2, 0x1E], "fcvt s0, h1"),
+        ([0x20, 0xC0, 0x23, 0x1E], "fcvt h0, s1"),
+    ];
+
+    for (bytes, expected) in cases {
+        let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+        match lifter.lift() {
+            Err(AArch64LifterError::UnsupportedInstruction { pc, text, .. }) => {
+                assert_eq!(pc, 0);
+                assert_eq!(text, expected);
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("strict lifting of {expected} succeeded"),
+        }
+    }
+}
+
+#[test]
+fn strict_lifts_exclusive_retry_loop() {
+    let bytes = [
+        0x20, 0xFC, 0x5F, 0xC8, // ldaxr x0, [x1]
+        0x00, 0x04, 0x00, 0x91, // add x0, x0, #1
+        0x20, 0xFC, 0x02, 0xC8, // stlxr w2, x0, [x1]
+        0xA2, 0xFF, 0xFF, 0x35, // cbnz w2, #-12
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+
+    assert!(lifter.lift().is_ok());
+}
+
+#[test]
+fn strict_lifts_hints_and_pstate_writes_without_effect() {
+    let bytes = [
+        0x3F, 0x20, 0x03, 0xD5, // yield
+        0x9F, 0x22, 0x03, 0xD5, // csdb
+        0x1F, 0x24, 0x03, 0xD5, // bti
+        0xDF, 0x42, 0x03, 0xD5, // msr daifset, #2
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+
+    assert!(lifter.lift().is_ok());
+}
@@ -0,0 +1,26 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, ReportKind};
+use aarch64_air_lifter::Lifter;
+
+#[test]
+fn report_unsupported_instructions() {
+    let bytes = [
+        0x20, 0x40, 0xC2, 0x1A, // crc32b w0, w1, w2
+        0x20, 0xD4, 0x22, 0x4E, // fadd v0.4s, v1.4s, v2.4s
+        0xFF, 0x2F, 0x03, 0xD5, // hint #0x7f
+        0x20, 0x40, 0xC2, 0x1A, // crc32b w0, w1, w2
+        0x01, 0x00, 0x00, 0xD4, // svc #0x0
+    ];
+
+    let lifter = AArch64Lifter::new(&bytes, &[]);
+    let (_code_region, report) = lifter.lift_with_report().unwrap();
+
+    assert_eq!(
+        report.by_mnemonic(ReportKind::Opaque),
+        vec![("crc32b", vec![0, 12])]
+    );
+    assert_eq!(report.counts(ReportKind::SkippedSimd), vec![("fadd", 1)]);
+    assert_eq!(
+        report.by_mnemonic(ReportKind::IgnoredSystem),
+        vec![("hint", vec![8]), ("svc", vec![16])]
+    );
+}
@@ -1,3 +1,14 @@
+mod call_mode;
+mod dot;
 mod insts;
+mod jump_tables;
+mod lift_errors;
+mod lift_mode;
+mod lift_report;
+mod orderings;
+mod syscalls;
+mod sysregs;
+mod traps;
+mod vector_registers;
 mod wasm;
 mod yaml_tests;
@@ -0,0 +1,101 @@
+use aarch64_air_lifter::arm64::{
+    AArch64Lifter, BarrierAccesses, Fence, LiftMode, MemoryOrdering, OrderingKind,
+};
+
+fn kinds(bytes: &[u8]) -> Vec<(u64, OrderingKind)> {
+    let (_code_region, orderings) = AArch64Lifter::new(bytes, &[])
+        .lift_with_orderings()
+        .unwrap();
+    orderings
+        .markers()
+        .iter()
+        .map(|marker| (marker.pc, marker.kind))
+        .collect()
+}
+
+#[test]
+fn lse_atomics_record_their_ordering() {
+    let bytes = [
+        0x02, 0x00, 0xe1, 0xf8, // ldaddal x1, x2, [x0]
+        0x1f, 0x00, 0x21, 0xb8, // stadd w1, [x0]
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+
+    assert_eq!(
+        kinds(&bytes),
+        [
+            (0, OrderingKind::Access(MemoryOrdering::AcquireRelease)),
+            (4, OrderingKind::Access(MemoryOrdering::Relaxed))
+        ]
+    );
+}
+
+#[test]
+fn barriers_and_acquire_release_are_recorded() {
+    let bytes = [
+        0xbf, 0x3b, 0x03, 0xd5, // dmb ish
+        0x20, 0xfc, 0xdf, 0x88, // ldar w0, [x1]
+        0x40, 0xfc, 0x9f, 0x88, // stlr w0, [x2]
+        0x9f, 0x3e, 0x03, 0xd5, // dsb st
+        0xdf, 0x3f, 0x03, 0xd5, // isb
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+
+    assert_eq!(
+        kinds(&bytes),
+        [
+            (0, OrderingKind::Fence(Fence::Dmb(BarrierAccesses::All))),
+            (4, OrderingKind::Access(MemoryOrdering::Acquire)),
+            (8, OrderingKind::Access(MemoryOrdering::Release)),
+            (12, OrderingKind::Fence(Fence::Dsb(BarrierAccesses::Stores))),
+            (16, OrderingKind::Fence(Fence::Isb)),
+        ]
+    );
+}
+
+#[test]
+fn barriers_are_not_reported() {
+    let bytes = [
+        0xbf, 0x3b, 0x03, 0xd5, // dmb ish
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let (_code_region, report) = AArch64Lifter::new(&bytes, &[]).lift_with_report().unwrap();
+
+    assert!(report.is_empty());
+}
+
+#[test]
+fn ordered_accesses_lift_in_strict_mode() {
+    let bytes = [
+        0xbf, 0x3b, 0x03, 0xd5, // dmb ish
+        0x20, 0xfc, 0xdf, 0x88, // ldar w0, [x1]
+        0x40, 0xfc, 0x9f, 0x88, // stlr w0, [x2]
+        0xdf, 0x3f, 0x03, 0xd5, // isb
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+    let (_code_region, orderings) = lifter.lift_with_orderings().unwrap();
+
+    assert_eq!(orderings.markers().len(), 4);
+    assert_eq!(orderings.fences().count(), 2);
+}
+
+#[test]
+fn lse_atomics_lift_in_strict_mode() {
+    let bytes = [
+        0x02, 0x00, 0xe1, 0xf8, // ldaddal x1, x2, [x0]
+        0x02, 0xfc, 0xe1, 0x88, // casal w1, w2, [x0]
+        0xC0, 0x03, 0x5F, 0xD6, // ret
+    ];
+    let lifter = AArch64Lifter::new(&bytes, &[]).with_mode(LiftMode::Strict);
+    let (_code_region, orderings) = lifter.lift_with_orderings().unwrap();
+
+    let kinds: Vec<_> = orderings.markers().iter().map(|m| m.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            OrderingKind::Access(MemoryOrdering::AcquireRelease),
+            OrderingKind::Access(MemoryOrdering::AcquireRelease)
+        ]
+    );
+}
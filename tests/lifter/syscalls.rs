@@ -0,0 +1,79 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, LinuxAarch64Syscalls, SyscallMode};
+use aarch64_air_lifter::Lifter;
+
+const BYTES: [u8; 24] = [
+    0x08, 0x08, 0x80, 0xd2, // mov x8, #64
+    0x20, 0x00, 0x80, 0xd2, // mov x0, #1
+    0x01, 0x00, 0x00, 0xd4, // svc #0
+    0xe8, 0x03, 0x01, 0xaa, // mov x8, x1
+    0x01, 0x00, 0x00, 0xd4, // svc #0
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+#[test]
+fn svc_is_ignored_by_default() {
+    let (_code_region, syscalls) = AArch64Lifter::new(&BYTES, &[])
+        .lift_with_syscalls()
+        .unwrap();
+
+    assert!(syscalls.is_empty());
+}
+
+#[test]
+fn modelled_svc_reads_its_inputs_and_clobbers_x0() {
+    let bytes = [
+        0x01, 0x00, 0x00, 0xd4, // svc #0
+        0xc0, 0x03, 0x5f, 0xd6, // ret
+    ];
+    let (code_region, syscalls) = AArch64Lifter::new(&bytes, &[])
+        .with_syscall_mode(SyscallMode::Model)
+        .lift_with_syscalls()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert_eq!(syscalls.len(), 1);
+    assert!(result.contains("v0 = i64.read_reg \"x8\""));
+    for (i, reg) in (1..=6).zip(0..=5) {
+        assert!(result.contains(&format!("v{i} = i64.read_reg \"x{reg}\"")));
+    }
+    assert!(result
+        .lines()
+        .any(|line| line.trim_start().starts_with("v7 = ") && line.contains("opaque")));
+    assert!(result.contains("write_reg.i64 v7, \"x0\""));
+}
+
+#[test]
+fn loading_x8_forgets_the_syscall_number() {
+    let bytes = [
+        0xa8, 0x0b, 0x80, 0xd2, // mov x8, #93
+        0xe7, 0x23, 0x40, 0xa9, // ldp x7, x8, [sp]
+        0x01, 0x00, 0x00, 0xd4, // svc #0
+        0xc0, 0x03, 0x5f, 0xd6, // ret
+    ];
+    let (_code_region, syscalls) = AArch64Lifter::new(&bytes, &[])
+        .with_syscall_mode(SyscallMode::Model)
+        .lift_with_syscalls()
+        .unwrap();
+
+    assert_eq!(syscalls.len(), 1);
+    assert_eq!(syscalls[0].number, None);
+}
+
+#[test]
+fn modelled_svc_is_named_by_the_table() {
+    let table = LinuxAarch64Syscalls;
+    let (_code_region, syscalls) = AArch64Lifter::new(&BYTES, &[])
+        .with_syscall_mode(SyscallMode::Model)
+        .with_syscall_table(&table)
+        .lift_with_syscalls()
+        .unwrap();
+
+    assert_eq!(syscalls.len(), 2);
+    assert_eq!(syscalls[0].pc, 8);
+    assert_eq!(syscalls[0].number, Some(64));
+    assert_eq!(syscalls[0].name.as_deref(), Some("write"));
+    // x8 is no longer a known constant
+    assert_eq!(syscalls[1].pc, 16);
+    assert_eq!(syscalls[1].number, None);
+    assert_eq!(syscalls[1].name, None);
+}
@@ -0,0 +1,57 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, AArch64LifterError, LiftMode};
+use aarch64_air_lifter::Lifter;
+use target_lexicon::{Aarch64Architecture, Architecture};
+use tnj::arch::get_arch;
+
+const BYTES: [u8; 12] = [
+    0x40, 0xd0, 0x3b, 0xd5, // mrs x0, tpidr_el0
+    0x40, 0xd0, 0x1b, 0xd5, // msr tpidr_el0, x0
+    0xc0, 0x03, 0x5f, 0xd6, // ret
+];
+
+fn arch_models(name: &str) -> bool {
+    get_arch(Architecture::Aarch64(Aarch64Architecture::Aarch64))
+        .unwrap()
+        .lookup_reg(&name.into())
+        .is_some()
+}
+
+#[test]
+fn named_system_register_is_accessed_or_reported() {
+    let (code_region, warnings) = AArch64Lifter::new(&BYTES, &[])
+        .lift_with_warnings()
+        .unwrap();
+    let air = code_region.display().to_string();
+
+    if arch_models("tpidr_el0") {
+        assert!(warnings.is_empty());
+        assert!(air.contains("v0 = i64.read_reg \"tpidr_el0\""));
+        assert!(air.contains("write_reg.i64 v1, \"tpidr_el0\""));
+    } else {
+        let pcs: Vec<_> = warnings
+            .iter()
+            .map(|w| match w {
+                AArch64LifterError::UnsupportedInstruction { pc, .. } => *pc,
+                e => panic!("unexpected warning: {e}"),
+            })
+            .collect();
+        assert_eq!(pcs, vec![0, 4]);
+        assert!(!air.contains("tpidr_el0"));
+    }
+}
+
+#[test]
+fn strict_rejects_unmodelled_system_register() {
+    let result = AArch64Lifter::new(&BYTES, &[])
+        .with_mode(LiftMode::Strict)
+        .lift();
+
+    match result {
+        Ok(_) => assert!(arch_models("tpidr_el0")),
+        Err(AArch64LifterError::UnsupportedInstruction { pc, .. }) => {
+            assert!(!arch_models("tpidr_el0"));
+            assert_eq!(pc, 0);
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+    }
+}
@@ -0,0 +1,25 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, TrapKind};
+
+#[test]
+fn traps_carry_their_code() {
+    let bytes = [
+        0x00, 0x7d, 0x20, 0xd4, // brk #0x3e8
+        0x20, 0x00, 0x40, 0xd4, // hlt #0x1
+        0x1f, 0xc1, 0x00, 0x00, // udf #0xc11f
+    ];
+
+    let (_code_region, traps) = AArch64Lifter::new(&bytes, &[]).lift_with_traps().unwrap();
+    let traps: Vec<_> = traps
+        .iter()
+        .map(|trap| (trap.pc, trap.kind, trap.code, trap.is_cranelift_stub()))
+        .collect();
+
+    assert_eq!(
+        traps,
+        [
+            (0, TrapKind::Breakpoint, 0x3e8, false),
+            (4, TrapKind::Halt, 0x1, false),
+            (8, TrapKind::Undefined, 0xc11f, true),
+        ]
+    );
+}
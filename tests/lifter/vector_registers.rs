@@ -0,0 +1,36 @@
+use aarch64_air_lifter::arm64::{AArch64Lifter, LiftMode};
+use aarch64_air_lifter::Lifter;
+use target_lexicon::{Aarch64Architecture, Architecture};
+use tnj::arch::get_arch;
+
+#[test]
+fn architecture_models_vector_registers() {
+    let arch = get_arch(Architecture::Aarch64(Aarch64Architecture::Aarch64)).unwrap();
+
+    for n in 0..32 {
+        assert!(
+            arch.lookup_reg(&format!("v{n}").into()).is_some(),
+            "v{n} is not modelled"
+        );
+    }
+}
+
+#[test]
+fn scalar_fp_lifts_in_strict_mode() {
+    let bytes = [
+        0x20, 0x28, 0x22, 0x1e, // fadd s0, s1, s2
+        0x20, 0xc0, 0x22, 0x1e, // fcvt d0, s1
+        0xc0, 0x03, 0x5f, 0xd6, // ret
+    ];
+    let code_region = AArch64Lifter::new(&bytes, &[])
+        .with_mode(LiftMode::Strict)
+        .lift()
+        .unwrap();
+    let result = code_region.display().to_string();
+
+    assert!(result.contains("i128.read_reg \"v1\""));
+    assert!(result.contains("i128.read_reg \"v2\""));
+    assert!(result.contains("f32.fadd"));
+    assert!(result.contains("fpext"));
+    assert!(result.contains("write_reg.i128"));
+}
@@ -17,6 +17,7 @@ struct TestSpec {
     bytes: Vec<u8>,
     directives: String,
     proofs: Option<Vec<u8>>,
+    base_address: Option<u64>,
     skip: Option<bool>,
 }
 
@@ -48,7 +49,8 @@ pub fn run_test_from_yaml(file: &str, test_name: &str) {
                     .as_ref()
                     .map(|bytes| &bytes[..])
                     .unwrap_or_default(),
-            );
+            )
+            .with_base_address(test.base_address.unwrap_or_default());
             let blob = lifter.lift().expect("Lifter failed");
             let result = blob.display().to_string();
 
@@ -96,7 +98,10 @@ pub fn run_test_from_yaml(file: &str, test_name: &str) {
                 &test.bytes,
                 test.proofs.as_ref().map(|bytes| &bytes[..]),
                 &test.directives,
-                CheckInstructionArgs::default()
+                CheckInstructionArgs {
+                    base_address: test.base_address.unwrap_or_default(),
+                    ..Default::default()
+                }
             ),
             "Test '{}' failed",
             test_name
@@ -114,6 +119,7 @@ fn pretty_print_yaml(test_file: &TestFile) -> String {
              bytes,
              directives,
              proofs,
+             base_address,
              skip,
          }| {
             let directives = directives.lines().fold(String::new(), |mut acc, rhs| {
@@ -136,6 +142,11 @@ fn pretty_print_yaml(test_file: &TestFile) -> String {
                 String::new()
             };
             let bytes = format_bytes(bytes);
+            let base_address = if let Some(base_address) = base_address {
+                format!("\n  base_address: {base_address:#x}")
+            } else {
+                String::new()
+            };
             let skip = if let Some(skip) = skip {
                 format!("\n  skip: {skip}")
             } else {
@@ -144,7 +155,7 @@ fn pretty_print_yaml(test_file: &TestFile) -> String {
             s.push_str(&format!(
                 "\
 - name: {name}
-  bytes: [{bytes}]{skip}{proofs}
+  bytes: [{bytes}]{base_address}{skip}{proofs}
   directives: |{directives}
 "
             ));
@@ -1,4 +1,5 @@
 pub mod common;
 mod completeness;
+mod elf_lifter;
 mod label_resolver;
 pub mod lifter;